@@ -1,3 +1,5 @@
+use crate::error::Error;
+
 #[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
 pub struct BGPError {
     error_code: ErrorCode,
@@ -19,6 +21,10 @@ impl BGPError {
     pub fn open(sub_code: OpenMessageError) -> BGPError {
         Self::new(ErrorCode::OpenMessage, sub_code)
     }
+
+    pub fn update(sub_code: UpdateMessageError) -> BGPError {
+        Self::new(ErrorCode::UpdateMessage, sub_code)
+    }
 }
 
 #[repr(u8)]
@@ -83,6 +89,7 @@ pub enum OpenMessageError {
     BadBGPIdentifier = 3,
     UnsupportedOptionalParameter = 4,
     UnacceptableHoldTime = 6,
+    RoleMismatch = 11,
 }
 
 impl From<OpenMessageError> for u8 {
@@ -90,3 +97,52 @@ impl From<OpenMessageError> for u8 {
         value as u8
     }
 }
+
+#[repr(u8)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub enum UpdateMessageError {
+    MalformedAttributeList = 1,
+    UnrecognizedWellKnownAttribute = 2,
+    MissingWellKnownAttribute = 3,
+    AttributeFlagsError = 4,
+    AttributeLengthError = 5,
+    InvalidOriginAttribute = 6,
+    InvalidNextHopAttribute = 8,
+    OptionalAttributeError = 9,
+    InvalidNetworkField = 10,
+    MalformedASPath = 11,
+}
+
+impl From<UpdateMessageError> for u8 {
+    fn from(value: UpdateMessageError) -> Self {
+        value as u8
+    }
+}
+
+/// This is the representation of the approaches to handle a malformed attribute in a Update packet,
+/// which are defined in [RFC7606, Section 2](https://www.rfc-editor.org/rfc/rfc7606#section-2).
+/// The approaches are ordered by their severity, so the most severe approach of multiple malformed
+/// attributes can be determined with `Iterator#max`.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Hash)]
+pub enum ErrorAction {
+    /// The malformed attribute is discarded and the Update packet is processed without the
+    /// attribute (Attribute Discard).
+    AttributeDiscard,
+
+    /// All routes of the Update packet are handled as if they were withdrawn by the peer
+    /// (Treat-as-withdraw).
+    TreatAsWithdraw,
+
+    /// The session with the peer is reset with a Notification packet (Session Reset).
+    SessionReset,
+}
+
+/// This is the diagnostic of a malformed attribute in a Update packet, which was read with the
+/// revised error handling. The diagnostic contains the type code of the attribute, the approach
+/// to handle the error and the error itself.
+#[derive(Clone, PartialEq, Eq, Debug, Hash)]
+pub struct UpdateDiagnostic {
+    pub type_code: u8,
+    pub action: ErrorAction,
+    pub error: Error,
+}
@@ -0,0 +1,188 @@
+use crate::bgp::error::UpdateDiagnostic;
+use crate::bgp::path_attr::Attribute;
+use crate::bgp::{Packet, PacketType, PathPrefix, SessionParameters};
+use crate::error::ErrorType;
+use crate::io::Buffer;
+use crate::{if_no_std, if_std};
+use crate::Result;
+use core::hash::{Hash, Hasher};
+use core::ops::Deref;
+
+if_no_std! {
+    use alloc::{collections::BTreeMap, format, sync::{Arc, Weak}, vec::Vec};
+}
+
+if_std! {
+    use std::{collections::BTreeMap, sync::{Arc, Weak}};
+}
+
+/// This is the representation of a interned list of path attributes. The handle is a reference to
+/// the list, which is shared by all routes with the same attributes, so cloning the handle is cheap.
+/// The hash of the list is computed once while interning, and handles of the same interner are
+/// compared by their pointer.
+#[derive(Clone, Debug)]
+pub struct InternedAttributes(Arc<InternedEntry>);
+
+#[derive(Debug)]
+struct InternedEntry {
+    hash: u64,
+    attributes: Vec<Attribute>
+}
+
+impl InternedAttributes {
+
+    pub fn attributes(&self) -> &[Attribute] {
+        &self.0.attributes
+    }
+
+    /// This function returns the number of handles, which are referencing the same list of
+    /// attributes. The interner doesn't hold a handle of the list.
+    pub fn references(&self) -> usize {
+        Arc::strong_count(&self.0)
+    }
+
+}
+
+impl Deref for InternedAttributes {
+    type Target = [Attribute];
+
+    fn deref(&self) -> &Self::Target {
+        &self.0.attributes
+    }
+}
+
+impl PartialEq for InternedAttributes {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0) || (self.0.hash == other.0.hash && self.0.attributes == other.0.attributes)
+    }
+}
+
+impl Eq for InternedAttributes {}
+
+impl Hash for InternedAttributes {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.hash.hash(state);
+    }
+}
+
+/// This is the representation of a Update packet, which attributes are interned.
+#[derive(Clone, PartialEq, Eq, Debug, Hash)]
+pub struct InternedUpdate {
+    pub withdrawn_routes: Vec<PathPrefix>,
+    pub nlri: Vec<PathPrefix>,
+    pub attributes: InternedAttributes
+}
+
+/// This is the representation of a interner, which deduplicates the path attributes of the routes
+/// in a RIB. In a full table, many prefixes share the same attributes, so each distinct list of
+/// attributes is only stored once. The attributes are sorted by their type code before interning,
+/// as recommended in [RFC4271, Section 5](https://www.rfc-editor.org/rfc/rfc4271#section-5), so
+/// lists with a different order of the same attributes share the same handle.
+///
+/// The interner only keeps a weak reference of each list, so a list is dropped with the last route,
+/// that uses the list. The weak references of dropped lists are removed lazily while interning
+/// lists with the same hash, or with [AttributeInterner::shrink].
+#[derive(Clone, Debug, Default)]
+pub struct AttributeInterner {
+    entries: BTreeMap<u64, Vec<Weak<InternedEntry>>>
+}
+
+impl AttributeInterner {
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// This function returns the shared handle of the attributes. If the interner already contains
+    /// the same attributes, the existing handle is returned and the attributes are dropped.
+    pub fn intern(&mut self, mut attributes: Vec<Attribute>) -> InternedAttributes {
+        attributes.sort_by_key(|attribute| u8::from(attribute.ty()));
+        let hasher = &mut FnvHasher::default();
+        attributes.hash(hasher);
+        let hash = hasher.finish();
+
+        let bucket = self.entries.entry(hash).or_default();
+        bucket.retain(|entry| entry.strong_count() > 0);
+        if let Some(entry) = bucket.iter()
+            .filter_map(Weak::upgrade)
+            .find(|entry| entry.attributes == attributes) {
+            return InternedAttributes(entry);
+        }
+
+        let entry = Arc::new(InternedEntry {
+            hash,
+            attributes
+        });
+        bucket.push(Arc::downgrade(&entry));
+        InternedAttributes(entry)
+    }
+
+    /// This function reads a Update packet from the buffer and interns the attributes of the packet.
+    /// Other packets are rejected.
+    pub fn read_update(&mut self, buffer: &mut Buffer, parameters: &SessionParameters) -> Result<InternedUpdate> {
+        self.intern_update(Packet::read_with(buffer, parameters)?)
+    }
+
+    /// This function reads a Update packet from the buffer with the revised error handling of
+    /// [RFC7606](https://www.rfc-editor.org/rfc/rfc7606) and interns the attributes of the packet.
+    /// The diagnostics of the malformed attributes are returned with the packet.
+    pub fn read_update_revised(&mut self, buffer: &mut Buffer, parameters: &SessionParameters) -> Result<(InternedUpdate, Vec<UpdateDiagnostic>)> {
+        let (packet, diagnostics) = Packet::read_revised(buffer, parameters)?;
+        Ok((self.intern_update(packet)?, diagnostics))
+    }
+
+    fn intern_update(&mut self, packet: Packet) -> Result<InternedUpdate> {
+        match packet {
+            Packet::Update(withdrawn_routes, nlri, attributes) => Ok(InternedUpdate {
+                withdrawn_routes,
+                nlri,
+                attributes: self.intern(attributes)
+            }),
+            packet => Err(ErrorType::ReadError.err(format!("Expected Update packet, but got {:?} packet", PacketType::from(&packet))))
+        }
+    }
+
+    /// This function removes the weak references of all lists of attributes, which were dropped.
+    pub fn shrink(&mut self) {
+        self.entries.retain(|_, bucket| {
+            bucket.retain(|entry| entry.strong_count() > 0);
+            !bucket.is_empty()
+        });
+    }
+
+    /// This function returns the number of distinct lists of attributes, which are used by routes.
+    pub fn len(&self) -> usize {
+        self.entries.values()
+            .flatten()
+            .filter(|entry| entry.strong_count() > 0)
+            .count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+}
+
+/// This is a implementation of the FNV-1a hash function, which is used to hash the attributes
+/// without the hashers of the std library.
+struct FnvHasher(u64);
+
+impl Default for FnvHasher {
+    fn default() -> Self {
+        Self(0xcbf29ce484222325)
+    }
+}
+
+impl Hasher for FnvHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= *byte as u64;
+            self.0 = self.0.wrapping_mul(0x100000001b3);
+        }
+    }
+}
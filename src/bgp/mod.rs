@@ -1,20 +1,28 @@
-use crate::bgp::error::{BGPError, ErrorCode, HeaderError, OpenMessageError};
-use crate::bgp::opt_params::OptionalParameter;
-use crate::error::ErrorType;
+use crate::bgp::error::{BGPError, ErrorAction, ErrorCode, HeaderError, OpenMessageError, UpdateDiagnostic, UpdateMessageError};
+use crate::bgp::opt_params::{Capability, OptionalParameter, AFI, SAFI};
+use crate::error::{Error, ErrorType};
 use crate::io::{Buffer, ByteOrder, WriteRead};
 use crate::Result;
 use crate::{if_no_std, if_std};
-use crate::bgp::path_attr::Attribute;
+use core::fmt::{Display, Formatter};
+use core::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use core::str::FromStr;
+#[cfg(feature = "bgp_multiprotocol")]
+use crate::bgp::path_attr::{AttributeValue, MPNLRI};
+use crate::bgp::path_attr::{missing_attribute, read_attributes_revised, treat_as_withdraw, validate_attributes, Attribute};
 
 pub mod error;
+pub mod intern;
 pub mod opt_params;
 pub mod path_attr;
 
 if_no_std! {
     use {
         alloc::{
+            vec,
             vec::Vec,
-            format
+            format,
+            string::{String, ToString}
         },
         core::mem
     };
@@ -395,7 +403,7 @@ pub enum Packet {
     /// ## Meaning of the packet
     /// This packet is there for the actual task of BGP, for transferring routes between two peers.
     /// This packet is therefore usually the most sent packet in a BGP connection.
-    Update(Vec<RoutePrefix>, Vec<RoutePrefix>, Vec<Attribute>),
+    Update(Vec<PathPrefix>, Vec<PathPrefix>, Vec<Attribute>),
 
     /// This is the representation of the [RFC4271](https://www.rfc-editor.org/rfc/rfc4271)-defined
     /// BGP Notification packet with a minimal size of 23 bytes and a id of 3. The layout of the
@@ -431,8 +439,128 @@ pub enum Packet {
     RouteRefresh,
 }
 
+/// This structure contains the parameters of a BGP session, which are negotiated by the exchange of
+/// the Open packets and change the encoding of the Update packets. The default parameters are the
+/// parameters of a session between two routers that support four-octet AS numbers.
+///
+/// ## Usage of the parameters
+/// The parameters are used to read and write packets with the `Packet#read_with` and
+/// `Packet#write_with` functions. Here is an example for a session with a router, that doesn't
+/// support four-octet AS numbers:
+/// ```rust
+/// use zephyr_route::bgp::{Packet, SessionParameters};
+/// use zephyr_route::io::{Buffer, ByteOrder};
+/// let parameters = SessionParameters { four_octet_as: false, ..Default::default() };
+/// let buffer = &mut Buffer::empty(ByteOrder::BigEndian);
+/// Packet::KeepAlive.write_with(buffer, &parameters).unwrap();
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug, Hash)]
+pub struct SessionParameters {
+    /// This field indicates, that both routers support four-octet AS numbers, as described in
+    /// [RFC6793](https://www.rfc-editor.org/rfc/rfc6793). If this field is false, all AS numbers
+    /// in the AS_PATH and AGGREGATOR attributes are encoded as 2-byte AS numbers.
+    pub four_octet_as: bool,
+    /// This field enables the normalisation of the received prefixes. The host bits behind the
+    /// prefix length are cleared, so prefixes with different host bits are the same prefix. This
+    /// field isn't negotiated with the peer and is disabled by default.
+    pub normalize_prefixes: bool,
+    /// This field contains the address families, for which the peer sends path identifiers in the
+    /// NLRI, as described in [RFC7911, Section 4](https://www.rfc-editor.org/rfc/rfc7911#section-4).
+    pub add_path_receive: Vec<(AFI, SAFI)>,
+    /// This field contains the address families, for which the local router sends path identifiers
+    /// in the NLRI.
+    pub add_path_send: Vec<(AFI, SAFI)>,
+    /// This field contains the address families, for which both routers announced the Multiple
+    /// Labels capability, with the maximum number of labels the peer is able to receive, as
+    /// described in [RFC8277, Section 2.1](https://www.rfc-editor.org/rfc/rfc8277#section-2.1).
+    /// The labeled prefixes of other address families contain a single label.
+    pub multiple_labels: Vec<(AFI, SAFI, u8)>,
+}
+
+impl Default for SessionParameters {
+    fn default() -> Self {
+        Self {
+            four_octet_as: true,
+            normalize_prefixes: false,
+            add_path_receive: Vec::new(),
+            add_path_send: Vec::new(),
+            multiple_labels: Vec::new(),
+        }
+    }
+}
+
+impl SessionParameters {
+    /// This function creates the parameters of the session from the capabilities sent by the local
+    /// router and the capabilities received from the peer. A parameter is only enabled, if both
+    /// routers announced the capability.
+    pub fn negotiate(local: &[Capability], remote: &[Capability]) -> Self {
+        let four_octet_as = |capabilities: &[Capability]| {
+            capabilities
+                .iter()
+                .any(|capability| matches!(capability, Capability::FourOctetASNumberSupport(_)))
+        };
+
+        // RFC7911, Section 4: Path identifiers are only sent for a address family, if the sender
+        // announced the Send mode and the receiver announced the Receive mode
+        let add_path = |sender: &[Capability], receiver: &[Capability]| {
+            let modes = |capabilities: &[Capability]| capabilities
+                .iter()
+                .filter_map(|capability| match capability {
+                    Capability::AddPath(families) => Some(families.clone()),
+                    _ => None
+                })
+                .flatten()
+                .collect::<Vec<_>>();
+            let receiver = modes(receiver);
+
+            modes(sender)
+                .into_iter()
+                .filter(|(afi, safi, mode)| mode.can_send() && receiver.iter()
+                    .any(|(other_afi, other_safi, other_mode)| other_afi == afi && other_safi == safi && other_mode.can_receive()))
+                .map(|(afi, safi, _)| (afi, safi))
+                .collect::<Vec<_>>()
+        };
+
+        let multiple_labels = |capabilities: &[Capability]| capabilities
+            .iter()
+            .filter_map(|capability| match capability {
+                Capability::MultipleLabels(families) => Some(families.clone()),
+                _ => None
+            })
+            .flatten()
+            .collect::<Vec<_>>();
+        let local_labels = multiple_labels(local);
+
+        Self {
+            four_octet_as: four_octet_as(local) && four_octet_as(remote),
+            add_path_receive: add_path(remote, local),
+            add_path_send: add_path(local, remote),
+            multiple_labels: multiple_labels(remote)
+                .into_iter()
+                .filter(|(afi, safi, _)| local_labels.iter().any(|(local_afi, local_safi, _)| local_afi == afi && local_safi == safi))
+                .collect(),
+            ..Default::default()
+        }
+    }
+}
+
 impl WriteRead for Packet {
     fn write(&self, buffer: &mut Buffer) -> Result<()> {
+        self.write_with(buffer, &SessionParameters::default())
+    }
+
+    fn read(buffer: &mut Buffer) -> Result<Self>
+    where
+        Self: Sized,
+    {
+        Self::read_with(buffer, &SessionParameters::default())
+    }
+}
+
+impl Packet {
+    /// This function writes the packet with the header into the buffer. The encoding of the Update
+    /// packet depends on the parameters, that were negotiated with the peer.
+    pub fn write_with(&self, buffer: &mut Buffer, parameters: &SessionParameters) -> Result<()> {
         let temp_buffer = &mut Buffer::empty(ByteOrder::BigEndian);
 
         match self {
@@ -452,24 +580,21 @@ impl WriteRead for Packet {
             Self::Update(withdrawn_routes, nlri, attributes) => {
                 let withdrawn_routes_buffer = &mut Buffer::empty(ByteOrder::BigEndian);
                 for route in withdrawn_routes {
-                    route.write(withdrawn_routes_buffer)?;
+                    route.write_with(withdrawn_routes_buffer, AFI::IPv4, SAFI::Unicast, parameters)?;
                 }
                 (withdrawn_routes_buffer.len() as u16).write(temp_buffer)?;
                 withdrawn_routes_buffer.write_buffer(temp_buffer)?;
 
                 let attributes_buffer = &mut Buffer::empty(ByteOrder::BigEndian);
                 for attribute in attributes {
-                    attribute.write(attributes_buffer)?;
+                    attribute.write_with(attributes_buffer, parameters)?;
                 }
                 (attributes_buffer.len() as u16).write(temp_buffer)?;
                 attributes_buffer.write_buffer(temp_buffer)?;
 
-                let nlri_buffer = &mut Buffer::empty(ByteOrder::BigEndian);
                 for route in nlri {
-                    route.write(nlri_buffer)?;
+                    route.write_with(temp_buffer, AFI::IPv4, SAFI::Unicast, parameters)?;
                 }
-                (nlri_buffer.len() as u16).write(temp_buffer)?;
-                nlri_buffer.write_buffer(temp_buffer)?;
             }
             Self::KeepAlive => {}
             Self::Notification(error_code, sub_code, data) => {
@@ -487,13 +612,35 @@ impl WriteRead for Packet {
         Ok(())
     }
 
-    fn read(buffer: &mut Buffer) -> Result<Self>
-    where
-        Self: Sized,
-    {
+    /// This function reads the packet with the header from the buffer. The encoding of the Update
+    /// packet depends on the parameters, that were negotiated with the peer.
+    pub fn read_with(buffer: &mut Buffer, parameters: &SessionParameters) -> Result<Self> {
+        let header = BGPHeader::read(buffer)?;
+        let buffer = &mut Buffer::read_buffer(buffer, (header.length as usize) - 19)?;
+        Self::read_body(&header, buffer, parameters, None)
+    }
+
+    /// This function reads the packet with the header from the buffer with the revised error
+    /// handling for Update packets, as defined in [RFC7606](https://www.rfc-editor.org/rfc/rfc7606).
+    /// Instead of failing on the first malformed attribute, the attribute is handled with the
+    /// approach defined for the type of the attribute:
+    /// - Session Reset: The error is returned by this function and the session should be reset.
+    /// - Treat-as-withdraw: All routes of the Update packet are returned as withdrawn routes and
+    /// only the MP_REACH_NLRI (as MP_UNREACH_NLRI) and MP_UNREACH_NLRI attributes are kept.
+    /// - Attribute Discard: The malformed attribute is removed from the Update packet.
+    ///
+    /// The returned packet is returned with a diagnostic for every malformed attribute. The NLRI of
+    /// the packet is located with the Total Path Attribute Length, so the NLRI is also read if the
+    /// attributes are malformed.
+    pub fn read_revised(buffer: &mut Buffer, parameters: &SessionParameters) -> Result<(Self, Vec<UpdateDiagnostic>)> {
         let header = BGPHeader::read(buffer)?;
         let buffer = &mut Buffer::read_buffer(buffer, (header.length as usize) - 19)?;
+        let mut diagnostics = Vec::new();
+        let packet = Self::read_body(&header, buffer, parameters, Some(&mut diagnostics))?;
+        Ok((packet, diagnostics))
+    }
 
+    fn read_body(header: &BGPHeader, buffer: &mut Buffer, parameters: &SessionParameters, diagnostics: Option<&mut Vec<UpdateDiagnostic>>) -> Result<Self> {
         match header.ty {
             PacketType::Open => {
                 let version = u8::read(buffer)?;
@@ -525,23 +672,42 @@ impl WriteRead for Packet {
                 let withdrawn_routes_buffer = &mut Buffer::read_buffer(buffer, length as usize)?;
                 let mut withdrawn_routes = Vec::new();
                 while withdrawn_routes_buffer.remaining() > 0 {
-                    withdrawn_routes.push(RoutePrefix::read(withdrawn_routes_buffer)?);
+                    withdrawn_routes.push(PathPrefix::read_with(withdrawn_routes_buffer, AFI::IPv4, SAFI::Unicast, parameters)?);
                 }
 
                 let length = u16::read(buffer)?;
                 let attributes_buffer = &mut Buffer::read_buffer(buffer, length as usize)?;
-                let mut attributes = Vec::new();
-                while attributes_buffer.remaining() > 0 {
-                    attributes.push(Attribute::read(attributes_buffer)?);
-                }
 
-                let length = u16::read(buffer)?;
-                let nlri_buffer = &mut Buffer::read_buffer(buffer, length as usize)?;
+                // RFC4271, Section 4.3: The NLRI is the remaining part of the packet
                 let mut nlri = Vec::new();
-                while nlri_buffer.remaining() > 0 {
-                    nlri.push(RoutePrefix::read(nlri_buffer)?);
+                while buffer.remaining() > 0 {
+                    nlri.push(PathPrefix::read_with(buffer, AFI::IPv4, SAFI::Unicast, parameters)?);
                 }
 
+                let Some(diagnostics) = diagnostics else {
+                    let mut attributes = Vec::new();
+                    while attributes_buffer.remaining() > 0 {
+                        attributes.push(Attribute::read_with(attributes_buffer, parameters)?);
+                    }
+                    validate_attributes(&attributes, !nlri.is_empty())?;
+                    return Ok(Packet::Update(withdrawn_routes, nlri, attributes));
+                };
+
+                let attributes = read_attributes_revised(attributes_buffer, parameters, diagnostics)?;
+
+                // RFC7606, Section 3 (d): A missing well-known attribute is handled with Treat-as-withdraw
+                if let Some(ty) = missing_attribute(&attributes, !nlri.is_empty()) {
+                    diagnostics.push(UpdateDiagnostic {
+                        type_code: u8::from(ty),
+                        action: ErrorAction::TreatAsWithdraw,
+                        error: ErrorType::BGPError(BGPError::update(UpdateMessageError::MissingWellKnownAttribute))
+                            .err(format!("Missing well-known attribute {ty:?}!"))
+                    });
+                }
+                if diagnostics.iter().any(|diagnostic| diagnostic.action == ErrorAction::TreatAsWithdraw) {
+                    withdrawn_routes.append(&mut nlri);
+                    return Ok(Packet::Update(withdrawn_routes, nlri, treat_as_withdraw(attributes)));
+                }
                 Ok(Packet::Update(withdrawn_routes, nlri, attributes))
             },
             PacketType::Notification => {
@@ -562,9 +728,7 @@ impl WriteRead for Packet {
             }
         }
     }
-}
 
-impl Packet {
     /// TODO: Do description
     #[cfg(feature = "std")]
     pub fn receive(edge: impl Into<String>, stream: &mut impl Read) -> Result<Option<Vec<Packet>>> {
@@ -585,7 +749,11 @@ impl Packet {
         let buffer = &mut Buffer::from_vec(bytes, ByteOrder::BigEndian);
         let mut packets = Vec::new();
         while buffer.remaining() >= 19 { // 19 is the minimal length of an BGP packet
-            packets.push(Self::read(buffer)?);
+            let packet = Self::read(buffer)?;
+            if_log! {
+                log::trace!("Read packet {}", packet.summary())
+            }
+            packets.push(packet);
         }
 
         if buffer.remaining() > 0 {
@@ -617,6 +785,106 @@ impl Packet {
         Ok(())
     }
 
+    /// This function returns a compact summary of the packet for logging. The summary of a Update
+    /// packet contains the withdrawn and announced prefixes including the prefixes of the
+    /// MP_REACH_NLRI and MP_UNREACH_NLRI attributes and the types of the attributes. Only the first
+    /// prefixes of long lists are shown with the number of the omitted prefixes.
+    ///
+    /// ```text
+    /// Update withdrawn=[] announced=[192.0.2.0/24, 2001:db8::/32] attributes=[Origin, ASPath, NextHop]
+    /// ```
+    pub fn summary(&self) -> String {
+        const SHOWN_PREFIXES: usize = 4;
+
+        let Self::Update(withdrawn_routes, nlri, attributes) = self else {
+            return format!("{:?}", PacketType::from(self));
+        };
+
+        #[allow(unused_mut)]
+        let mut withdrawn_routes: Vec<&PathPrefix> = withdrawn_routes.iter().collect();
+        #[allow(unused_mut)]
+        let mut nlri: Vec<&PathPrefix> = nlri.iter().collect();
+        #[cfg(feature = "bgp_multiprotocol")]
+        for attribute in attributes {
+            match attribute.value() {
+                AttributeValue::MPReachableNLRI(_, _, _, MPNLRI::Prefixes(prefixes)) => nlri.extend(prefixes),
+                AttributeValue::MPUnreachableNLRI(_, _, MPNLRI::Prefixes(prefixes)) => withdrawn_routes.extend(prefixes),
+                _ => {}
+            }
+        }
+
+        let prefixes = |prefixes: &[&PathPrefix]| {
+            let mut summary = prefixes.iter()
+                .take(SHOWN_PREFIXES)
+                .map(|prefix| prefix.to_string())
+                .collect::<Vec<String>>()
+                .join(", ");
+            if prefixes.len() > SHOWN_PREFIXES {
+                summary.push_str(&format!(" (+{})", prefixes.len() - SHOWN_PREFIXES));
+            }
+            summary
+        };
+        let attributes = attributes.iter()
+            .map(|attribute| format!("{:?}", attribute.ty()))
+            .collect::<Vec<String>>()
+            .join(", ");
+        format!("Update withdrawn=[{}] announced=[{}] attributes=[{attributes}]", prefixes(&withdrawn_routes), prefixes(&nlri))
+    }
+
+    /// This function returns the prefixes, which occur more than once in the withdrawn routes and
+    /// the NLRI of the Update packet and the NLRI of the MP_REACH_NLRI and MP_UNREACH_NLRI
+    /// attributes. Only prefixes of the same address family are compared after the normalisation.
+    /// The path identifiers are only compared for the address families, for which ADD-PATH was
+    /// negotiated in the receive direction. As described in
+    /// [RFC4271, Section 4.3](https://www.rfc-editor.org/rfc/rfc4271#section-4.3), a prefix in the
+    /// withdrawn routes and in the NLRI should be handled as announced prefix.
+    pub fn duplicate_prefixes(&self, parameters: &SessionParameters) -> Vec<(AFI, SAFI, PathPrefix)> {
+        let Self::Update(withdrawn_routes, nlri, _attributes) = self else {
+            return Vec::new();
+        };
+
+        let mut families: Vec<(AFI, SAFI, Vec<PathPrefix>)> = Vec::new();
+        let mut insert = |afi: AFI, safi: SAFI, prefix: PathPrefix| {
+            let prefix = PathPrefix {
+                prefix: prefix.prefix.normalized(),
+                path_id: prefix.path_id.filter(|_| parameters.add_path_receive.contains(&(afi, safi)))
+            };
+            match families.iter_mut().find(|(other_afi, other_safi, _)| *other_afi == afi && *other_safi == safi) {
+                Some((_, _, prefixes)) => prefixes.push(prefix),
+                None => families.push((afi, safi, vec![prefix]))
+            }
+        };
+
+        for prefix in withdrawn_routes.iter().chain(nlri) {
+            insert(AFI::IPv4, SAFI::Unicast, *prefix);
+        }
+        #[cfg(feature = "bgp_multiprotocol")]
+        for attribute in _attributes {
+            let (afi, safi, mp_nlri) = match attribute.value() {
+                AttributeValue::MPReachableNLRI(afi, safi, _, mp_nlri) | AttributeValue::MPUnreachableNLRI(afi, safi, mp_nlri) => (*afi, *safi, mp_nlri),
+                _ => continue
+            };
+            match mp_nlri {
+                MPNLRI::Prefixes(prefixes) => prefixes.iter().for_each(|prefix| insert(afi, safi, *prefix)),
+                MPNLRI::LabeledPrefixes(prefixes) => prefixes.iter()
+                    .for_each(|prefix| insert(afi, safi, PathPrefix { prefix: prefix.prefix, path_id: prefix.path_id })),
+                _ => {}
+            }
+        }
+
+        let mut duplicates = Vec::new();
+        for (afi, safi, mut prefixes) in families {
+            prefixes.sort();
+            let mut family_duplicates: Vec<PathPrefix> = prefixes.windows(2)
+                .filter(|window| window[0] == window[1])
+                .map(|window| window[0])
+                .collect();
+            family_duplicates.dedup();
+            duplicates.extend(family_duplicates.into_iter().map(|prefix| (afi, safi, prefix)));
+        }
+        duplicates
+    }
+
     #[allow(clippy::len_without_is_empty)]
     pub fn len(&self) -> Result<usize> {
         let buffer = &mut Buffer::empty(ByteOrder::BigEndian);
@@ -625,19 +893,357 @@ impl Packet {
     }
 }
 
-#[derive(Clone, PartialEq, Eq, Debug, Hash)]
+/// This is the representation of a IP prefix in the NLRI and the withdrawn routes of the Update
+/// packet and in the NLRI of the MP_REACH_NLRI and MP_UNREACH_NLRI attributes. The prefix is
+/// encoded with the length of the prefix in bits and the significant bytes of the address, as
+/// defined in [RFC4271, Section 4.3](https://www.rfc-editor.org/rfc/rfc4271#section-4.3). The
+/// prefixes are ordered by the address family, the address and the length, so they can be stored
+/// in sorted tables.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Hash)]
 pub enum RoutePrefix {
-    IPv4(u8, Vec<u8>),
+    IPv4(Ipv4Addr, u8),
+    IPv6(Ipv6Addr, u8),
 }
 
 impl WriteRead for RoutePrefix {
     fn write(&self, buffer: &mut Buffer) -> Result<()> {
+        let prefix_length = self.prefix_length();
+        if prefix_length > self.max_prefix_length() {
+            return Err(ErrorType::WriteError.err(format!("Unable to write prefix {self:?}! The prefix length exceeds the length of the address")));
+        }
+
+        prefix_length.write(buffer)?;
+        self.write_address(buffer);
+        Ok(())
+    }
+
+    fn read(buffer: &mut Buffer) -> Result<Self>
+    where
+        Self: Sized,
+    {
+        Self::read_with(buffer, AFI::IPv4)
+    }
+}
+
+impl RoutePrefix {
+    pub fn new(address: IpAddr, prefix_length: u8) -> Self {
+        match address {
+            IpAddr::V4(address) => Self::IPv4(address, prefix_length),
+            IpAddr::V6(address) => Self::IPv6(address, prefix_length)
+        }
+    }
+
+    /// This function writes the prefix into the buffer. Prefixes of another address family than
+    /// the address family of the NLRI are rejected.
+    pub fn write_with(&self, buffer: &mut Buffer, afi: AFI) -> Result<()> {
+        self.check_afi(afi)?;
+        self.write(buffer)
+    }
+
+    fn check_afi(&self, afi: AFI) -> Result<()> {
+        if self.afi() != afi {
+            return Err(ErrorType::WriteError.err(format!("Unable to write prefix {self}! The prefix isn't a {afi:?} prefix")));
+        }
+        Ok(())
+    }
+
+    /// This function reads a prefix of the address family from the buffer. The prefixes of all
+    /// address families except IPv6 are read as IPv4 prefixes.
+    pub fn read_with(buffer: &mut Buffer, afi: AFI) -> Result<Self> {
+        let prefix_length = u8::read(buffer)?;
+        Self::read_address(buffer, afi, prefix_length)
+    }
+
+    /// This function reads the significant bytes of the address, which are covered by the prefix
+    /// length. The prefix length was already read from the buffer.
+    fn read_address(buffer: &mut Buffer, afi: AFI, prefix_length: u8) -> Result<Self> {
+        let significant_bytes = (prefix_length as usize).div_ceil(8);
+        let address_length = if afi == AFI::IPv6 { 16 } else { 4 };
+
+        // RFC4271, Section 6.3: A prefix length beyond the length of the address is a syntactic
+        // error of the NLRI field
+        if prefix_length as usize > address_length * 8 {
+            return Err(ErrorType::BGPError(BGPError::update(UpdateMessageError::InvalidNetworkField))
+                .err(format!("Unexpected prefix length {prefix_length} of {afi:?} prefix!")));
+        }
+
+        let mut octets = [0; 16];
+        octets[..significant_bytes].copy_from_slice(&buffer.read_bytes_vector(significant_bytes)?);
+        Ok(match afi {
+            AFI::IPv6 => Self::IPv6(Ipv6Addr::from(octets), prefix_length),
+            _ => Self::IPv4(Ipv4Addr::new(octets[0], octets[1], octets[2], octets[3]), prefix_length)
+        })
+    }
+
+    /// This function writes the significant bytes of the address without the prefix length.
+    fn write_address(&self, buffer: &mut Buffer) {
+        let significant_bytes = (self.prefix_length() as usize).div_ceil(8);
+        match self {
+            Self::IPv4(address, _) => buffer.write_bytes_slice(&address.octets()[..significant_bytes]),
+            Self::IPv6(address, _) => buffer.write_bytes_slice(&address.octets()[..significant_bytes])
+        }
+    }
+
+    /// This function reads a prefix of the address family from the buffer and normalizes the
+    /// prefix, if the normalisation is enabled in the parameters of the session.
+    pub fn read_with_parameters(buffer: &mut Buffer, afi: AFI, parameters: &SessionParameters) -> Result<Self> {
+        let prefix = Self::read_with(buffer, afi)?;
+        Ok(if parameters.normalize_prefixes { prefix.normalized() } else { prefix })
+    }
+
+    /// This function returns the prefix with cleared host bits behind the prefix length.
+    pub fn normalized(&self) -> Self {
+        let bits = self.prefix_length().min(self.max_prefix_length()) as u32;
         match self {
-            Self::IPv4(prefix_length, prefix) => {
-                prefix_length.write(buffer)?;
-                buffer.write_bytes_vector(prefix);
+            Self::IPv4(address, prefix_length) => {
+                let mask = u32::MAX.checked_shl(32 - bits).unwrap_or(0);
+                Self::IPv4(Ipv4Addr::from(u32::from(*address) & mask), *prefix_length)
+            },
+            Self::IPv6(address, prefix_length) => {
+                let mask = u128::MAX.checked_shl(128 - bits).unwrap_or(0);
+                Self::IPv6(Ipv6Addr::from(u128::from(*address) & mask), *prefix_length)
             }
         }
+    }
+
+    /// This function checks, if all host bits behind the prefix length are cleared.
+    pub fn is_normalized(&self) -> bool {
+        self.normalized() == *self
+    }
+
+    pub fn address(&self) -> IpAddr {
+        match self {
+            Self::IPv4(address, _) => IpAddr::V4(*address),
+            Self::IPv6(address, _) => IpAddr::V6(*address)
+        }
+    }
+
+    pub fn prefix_length(&self) -> u8 {
+        match self {
+            Self::IPv4(_, prefix_length) | Self::IPv6(_, prefix_length) => *prefix_length
+        }
+    }
+
+    /// This function returns the length of the address of the prefix in bits.
+    pub fn max_prefix_length(&self) -> u8 {
+        match self {
+            Self::IPv4(_, _) => 32,
+            Self::IPv6(_, _) => 128
+        }
+    }
+
+    pub fn afi(&self) -> AFI {
+        match self {
+            Self::IPv4(_, _) => AFI::IPv4,
+            Self::IPv6(_, _) => AFI::IPv6
+        }
+    }
+
+    /// This function checks, if the address is covered by the prefix. Addresses of another address
+    /// family are never covered.
+    pub fn contains_address(&self, address: IpAddr) -> bool {
+        self.contains(&Self::from(address))
+    }
+
+    /// This function checks, if the other prefix is equal to or more specific than this prefix. Only
+    /// the significant bits of this prefix are compared.
+    pub fn contains(&self, other: &RoutePrefix) -> bool {
+        if self.afi() != other.afi() || other.prefix_length() < self.prefix_length() {
+            return false;
+        }
+        let other = match other {
+            Self::IPv4(address, _) => Self::IPv4(*address, self.prefix_length()),
+            Self::IPv6(address, _) => Self::IPv6(*address, self.prefix_length())
+        };
+        self.normalized() == other.normalized()
+    }
+}
+
+/// The prefix is formatted in the CIDR notation with the address and the prefix length, like
+/// `192.0.2.0/24` or `2001:db8::/32`.
+impl Display for RoutePrefix {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(formatter, "{}/{}", self.address(), self.prefix_length())
+    }
+}
+
+/// The prefix is parsed from the CIDR notation. Prefixes with a prefix length beyond the length of
+/// the address and prefixes with host bits behind the prefix length are rejected, because these
+/// prefixes aren't in the canonical notation.
+impl FromStr for RoutePrefix {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        let invalid = |message: &str| ErrorType::ParseError.err(format!("Unable to parse prefix '{value}'! {message}"));
+        let (address, prefix_length) = value.split_once('/').ok_or_else(|| invalid("Expected <address>/<length>"))?;
+        let address = IpAddr::from_str(address).map_err(|_| invalid("Expected IPv4 or IPv6 address"))?;
+        let prefix_length = u8::from_str(prefix_length).map_err(|_| invalid("Expected prefix length"))?;
+
+        let prefix = Self::new(address, prefix_length);
+        if prefix_length > prefix.max_prefix_length() {
+            return Err(invalid(&format!("The prefix length exceeds {} bits", prefix.max_prefix_length())));
+        }
+        if !prefix.is_normalized() {
+            return Err(invalid(&format!("The host bits are set, expected {}", prefix.normalized())));
+        }
+        Ok(prefix)
+    }
+}
+
+impl From<(Ipv4Addr, u8)> for RoutePrefix {
+    fn from((address, prefix_length): (Ipv4Addr, u8)) -> Self {
+        Self::IPv4(address, prefix_length)
+    }
+}
+
+impl From<(Ipv6Addr, u8)> for RoutePrefix {
+    fn from((address, prefix_length): (Ipv6Addr, u8)) -> Self {
+        Self::IPv6(address, prefix_length)
+    }
+}
+
+impl From<(IpAddr, u8)> for RoutePrefix {
+    fn from((address, prefix_length): (IpAddr, u8)) -> Self {
+        Self::new(address, prefix_length)
+    }
+}
+
+/// The host address is converted into a prefix with the full length of the address.
+impl From<IpAddr> for RoutePrefix {
+    fn from(address: IpAddr) -> Self {
+        match address {
+            IpAddr::V4(address) => Self::IPv4(address, 32),
+            IpAddr::V6(address) => Self::IPv6(address, 128)
+        }
+    }
+}
+
+impl From<RoutePrefix> for (IpAddr, u8) {
+    fn from(prefix: RoutePrefix) -> Self {
+        (prefix.address(), prefix.prefix_length())
+    }
+}
+
+/// This is the representation of a prefix in the NLRI with the path identifier of the ADD-PATH
+/// extension, as defined in [RFC7911, Section 3](https://www.rfc-editor.org/rfc/rfc7911#section-3).
+/// The path identifier is only encoded, if the ADD-PATH capability was negotiated for the address
+/// family of the prefix. Prefixes without path identifier are written with the path identifier 0
+/// on these sessions.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Hash)]
+pub struct PathPrefix {
+    pub prefix: RoutePrefix,
+    pub path_id: Option<u32>,
+}
+
+impl PathPrefix {
+    pub fn new(prefix: RoutePrefix, path_id: u32) -> Self {
+        Self {
+            prefix,
+            path_id: Some(path_id),
+        }
+    }
+
+    /// This function writes the prefix into the buffer. The path identifier is written, if the
+    /// parameters of the session contain the address family in the sent ADD-PATH families.
+    pub fn write_with(&self, buffer: &mut Buffer, afi: AFI, safi: SAFI, parameters: &SessionParameters) -> Result<()> {
+        self.prefix.check_afi(afi)?;
+        if parameters.add_path_send.contains(&(afi, safi)) {
+            self.path_id.unwrap_or(0).write(buffer)?;
+        } else if self.path_id.is_some() {
+            return Err(ErrorType::WriteError.err(format!("Unable to write prefix {self}! ADD-PATH isn't negotiated for {afi:?} {safi:?}")));
+        }
+        self.prefix.write(buffer)
+    }
+
+    /// This function reads the prefix from the buffer. The path identifier is read, if the
+    /// parameters of the session contain the address family in the received ADD-PATH families.
+    pub fn read_with(buffer: &mut Buffer, afi: AFI, safi: SAFI, parameters: &SessionParameters) -> Result<Self> {
+        let path_id = match parameters.add_path_receive.contains(&(afi, safi)) {
+            true => Some(u32::read(buffer)?),
+            false => None
+        };
+        Ok(Self {
+            prefix: RoutePrefix::read_with_parameters(buffer, afi, parameters)?,
+            path_id,
+        })
+    }
+
+    pub fn normalized(&self) -> Self {
+        Self {
+            prefix: self.prefix.normalized(),
+            path_id: self.path_id,
+        }
+    }
+}
+
+impl From<RoutePrefix> for PathPrefix {
+    fn from(prefix: RoutePrefix) -> Self {
+        Self {
+            prefix,
+            path_id: None,
+        }
+    }
+}
+
+impl Display for PathPrefix {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> core::fmt::Result {
+        match self.path_id {
+            Some(path_id) => write!(formatter, "{} (path {path_id})", self.prefix),
+            None => write!(formatter, "{}", self.prefix)
+        }
+    }
+}
+
+/// This is the representation of a MPLS label stack entry in the NLRI of the labeled address
+/// families, as defined in [RFC8277, Section 2](https://www.rfc-editor.org/rfc/rfc8277#section-2).
+/// The entry is encoded in 3 bytes with the following layout:
+/// ```text
+/// 0                   1                   2
+/// 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                Label                  | TC  |S|
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// ```
+/// - Label: This 20-bit field contains the value of the label.
+/// - TC: This 3-bit field contains the traffic class.
+/// - S: This bit is set in the last label of the label stack (bottom of stack).
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Hash)]
+pub struct MPLSLabel {
+    pub label: u32,
+    pub traffic_class: u8,
+    pub bottom_of_stack: bool,
+}
+
+impl MPLSLabel {
+    /// This is the label field 0x800000, which is sent in the NLRI of withdrawn routes for the
+    /// compatibility with [RFC3107](https://www.rfc-editor.org/rfc/rfc3107), as described in
+    /// [RFC8277, Section 2.4](https://www.rfc-editor.org/rfc/rfc8277#section-2.4).
+    pub const WITHDRAWAL: Self = Self {
+        label: 0x80000,
+        traffic_class: 0,
+        bottom_of_stack: false,
+    };
+
+    pub fn new(label: u32, bottom_of_stack: bool) -> Self {
+        Self {
+            label,
+            traffic_class: 0,
+            bottom_of_stack,
+        }
+    }
+
+    pub fn is_withdrawal(&self) -> bool {
+        *self == Self::WITHDRAWAL
+    }
+}
+
+impl WriteRead for MPLSLabel {
+    fn write(&self, buffer: &mut Buffer) -> Result<()> {
+        if self.label > 0xFFFFF || self.traffic_class > 0b111 {
+            return Err(ErrorType::WriteError.err(format!("Unable to write label {self:?}! The label is limited to 20 bits and the traffic class to 3 bits")));
+        }
+        let value = (self.label << 4) | ((self.traffic_class as u32) << 1) | self.bottom_of_stack as u32;
+        buffer.write_bytes_slice(&value.to_be_bytes()[1..]);
         Ok(())
     }
 
@@ -645,10 +1251,165 @@ impl WriteRead for RoutePrefix {
     where
         Self: Sized,
     {
-        let prefix_length = u8::read(buffer)?;
-        Ok(Self::IPv4(
-            prefix_length,
-            buffer.read_bytes_vector((prefix_length as usize + 7) / 8)?,
-        ))
+        let [first, second, third] = buffer.read_bytes_array::<3>()?;
+        let value = u32::from_be_bytes([0, first, second, third]);
+        Ok(Self {
+            label: value >> 4,
+            traffic_class: ((value >> 1) & 0b111) as u8,
+            bottom_of_stack: value & 1 != 0,
+        })
+    }
+}
+
+/// This is the representation of a prefix with a stack of MPLS labels in the NLRI of the labeled
+/// unicast address family (SAFI 4), as defined in [RFC8277, Section 2](https://www.rfc-editor.org/rfc/rfc8277#section-2).
+/// The layout of the NLRI is shown below:
+/// ```text
+/// +---------------------------+
+/// |   Length (1 octet)        |
+/// +---------------------------+
+/// |   Label (3 octets)        |
+/// +---------------------------+
+/// .............................
+/// +---------------------------+
+/// |   Prefix (variable)       |
+/// +---------------------------+
+/// ```
+/// The length contains the number of bits of the labels and the prefix. The NLRI only contains
+/// multiple labels, if the Multiple Labels capability was negotiated for the address family.
+/// Otherwise the NLRI contains a single label. The path identifier of the ADD-PATH extension is
+/// encoded in front of the length like in the [PathPrefix].
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Hash)]
+pub struct LabeledPrefix {
+    pub prefix: RoutePrefix,
+    pub labels: Vec<MPLSLabel>,
+    pub path_id: Option<u32>,
+}
+
+impl LabeledPrefix {
+    /// This function creates a prefix with the stack of labels. The bottom-of-stack bit is set in
+    /// the last label.
+    pub fn new(prefix: RoutePrefix, labels: &[u32]) -> Self {
+        Self {
+            prefix,
+            labels: labels.iter()
+                .enumerate()
+                .map(|(index, label)| MPLSLabel::new(*label, index + 1 == labels.len()))
+                .collect(),
+            path_id: None,
+        }
+    }
+
+    /// This function creates a prefix for the MP_UNREACH_NLRI attribute with the compatibility
+    /// label 0x800000.
+    pub fn withdrawal(prefix: RoutePrefix) -> Self {
+        Self {
+            prefix,
+            labels: vec![MPLSLabel::WITHDRAWAL],
+            path_id: None,
+        }
+    }
+
+    /// This function writes the prefix of the MP_REACH_NLRI attribute into the buffer. Multiple
+    /// labels are only written, if the peer is able to receive the number of labels.
+    pub fn write_with(&self, buffer: &mut Buffer, afi: AFI, safi: SAFI, parameters: &SessionParameters) -> Result<()> {
+        let max_labels = parameters.multiple_labels.iter()
+            .find(|(other_afi, other_safi, _)| *other_afi == afi && *other_safi == safi)
+            .map_or(1, |(_, _, count)| *count as usize);
+        if self.labels.is_empty() || self.labels.len() > max_labels {
+            return Err(ErrorType::WriteError.err(format!("Unable to write prefix {}! Expected 1 to {max_labels} labels, but got {} labels", self.prefix, self.labels.len())));
+        }
+        self.write_labeled(buffer, afi, safi, parameters, &self.labels)
+    }
+
+    /// This function writes the prefix of the MP_UNREACH_NLRI attribute into the buffer. As
+    /// described in [RFC8277, Section 2.4](https://www.rfc-editor.org/rfc/rfc8277#section-2.4),
+    /// withdrawn routes contain exactly one compatibility field with the value 0x800000, so the
+    /// labels of the prefix aren't written.
+    pub fn write_withdrawal(&self, buffer: &mut Buffer, afi: AFI, safi: SAFI, parameters: &SessionParameters) -> Result<()> {
+        self.write_labeled(buffer, afi, safi, parameters, &[MPLSLabel::WITHDRAWAL])
+    }
+
+    fn write_labeled(&self, buffer: &mut Buffer, afi: AFI, safi: SAFI, parameters: &SessionParameters, labels: &[MPLSLabel]) -> Result<()> {
+        self.prefix.check_afi(afi)?;
+        let length = labels.len() * 24 + self.prefix.prefix_length() as usize;
+        if self.prefix.prefix_length() > self.prefix.max_prefix_length() || length > u8::MAX as usize {
+            return Err(ErrorType::WriteError.err(format!("Unable to write prefix {}! The length of the NLRI exceeds 255 bits", self.prefix)));
+        }
+
+        if parameters.add_path_send.contains(&(afi, safi)) {
+            self.path_id.unwrap_or(0).write(buffer)?;
+        } else if self.path_id.is_some() {
+            return Err(ErrorType::WriteError.err(format!("Unable to write prefix {}! ADD-PATH isn't negotiated for {afi:?} {safi:?}", self.prefix)));
+        }
+        (length as u8).write(buffer)?;
+        for label in labels {
+            label.write(buffer)?;
+        }
+        self.prefix.write_address(buffer);
+        Ok(())
+    }
+
+    /// This function reads the prefix of the MP_REACH_NLRI attribute from the buffer. If the
+    /// Multiple Labels capability was negotiated for the address family, the labels are read until
+    /// the bottom-of-stack bit is found.
+    pub fn read_with(buffer: &mut Buffer, afi: AFI, safi: SAFI, parameters: &SessionParameters) -> Result<Self> {
+        let multiple_labels = parameters.multiple_labels.iter()
+            .any(|(other_afi, other_safi, _)| *other_afi == afi && *other_safi == safi);
+        Self::read_labeled(buffer, afi, safi, parameters, multiple_labels)
+    }
+
+    /// This function reads the prefix of the MP_UNREACH_NLRI attribute from the buffer. The
+    /// withdrawn routes contain exactly one compatibility field, which is read as label regardless
+    /// of the value.
+    pub fn read_withdrawal(buffer: &mut Buffer, afi: AFI, safi: SAFI, parameters: &SessionParameters) -> Result<Self> {
+        Self::read_labeled(buffer, afi, safi, parameters, false)
+    }
+
+    fn read_labeled(buffer: &mut Buffer, afi: AFI, safi: SAFI, parameters: &SessionParameters, multiple_labels: bool) -> Result<Self> {
+        let path_id = match parameters.add_path_receive.contains(&(afi, safi)) {
+            true => Some(u32::read(buffer)?),
+            false => None
+        };
+
+        let mut length = u8::read(buffer)?;
+        let mut labels = Vec::new();
+        loop {
+            length = length.checked_sub(24).ok_or_else(|| ErrorType::BGPError(BGPError::update(UpdateMessageError::InvalidNetworkField))
+                .err(format!("Unexpected length of labeled {afi:?} prefix! The length doesn't cover the labels")))?;
+            let label = MPLSLabel::read(buffer)?;
+            labels.push(label);
+            if !multiple_labels || label.bottom_of_stack {
+                break;
+            }
+        }
+
+        let prefix = RoutePrefix::read_address(buffer, afi, length)?;
+        Ok(Self {
+            prefix: if parameters.normalize_prefixes { prefix.normalized() } else { prefix },
+            labels,
+            path_id,
+        })
+    }
+
+    pub fn normalized(&self) -> Self {
+        Self {
+            prefix: self.prefix.normalized(),
+            ..self.clone()
+        }
+    }
+}
+
+impl Display for LabeledPrefix {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> core::fmt::Result {
+        let labels = self.labels.iter()
+            .map(|label| label.label.to_string())
+            .collect::<Vec<String>>()
+            .join("/");
+        write!(formatter, "{} (labels {labels})", self.prefix)?;
+        if let Some(path_id) = self.path_id {
+            write!(formatter, " (path {path_id})")?;
+        }
+        Ok(())
     }
 }
@@ -6,6 +6,7 @@ use crate::io::{Buffer, ByteOrder, WriteRead};
 if_no_std! {
     use alloc::{
         format,
+        string::String,
         vec::Vec
     };
 }
@@ -72,6 +73,20 @@ pub enum Capability {
     #[cfg(feature = "bgp_route_refresh")]
     RouteRefresh,
     FourOctetASNumberSupport(u64),
+    /// This capability announces the support of BGPsec with the version, the direction and the
+    /// address family, as defined in [RFC8205, Section 2.1](https://www.rfc-editor.org/rfc/rfc8205#section-2.1).
+    BGPsec(u8, BGPsecDirection, AFI),
+    /// This capability announces the address families, for which the router is able to send or
+    /// receive multiple paths with path identifiers, as defined in
+    /// [RFC7911, Section 4](https://www.rfc-editor.org/rfc/rfc7911#section-4).
+    AddPath(Vec<(AFI, SAFI, AddPathMode)>),
+    /// This capability announces the address families, for which the router is able to receive
+    /// multiple labels, with the maximum number of labels, as defined in
+    /// [RFC8277, Section 2.1](https://www.rfc-editor.org/rfc/rfc8277#section-2.1).
+    MultipleLabels(Vec<(AFI, SAFI, u8)>),
+    /// This capability announces the role of the router in the peering relationship, as defined in
+    /// [RFC9234, Section 4.1](https://www.rfc-editor.org/rfc/rfc9234#section-4.1).
+    Role(Role),
     #[cfg(feature = "bgp_route_refresh")]
     EnhancedRouteRefresh,
     LongLivedGracefulRestart,
@@ -100,6 +115,67 @@ impl WriteRead for Capability {
             Self::FourOctetASNumberSupport(autonomous_system) => {
                 autonomous_system.write(temp_buffer)?
             }
+            Self::BGPsec(version, direction, afi) => {
+                if *version > 0x0F {
+                    return Err(ErrorType::WriteError.err(format!("Unexpected BGPsec version {version}! The version is limited to 4 bits")));
+                }
+                let direction = match direction {
+                    BGPsecDirection::Receive => 0,
+                    BGPsecDirection::Send => 0b00001000
+                };
+                ((version << 4) | direction).write(temp_buffer)?;
+                match (*afi).into() {
+                    Ok(value) => value.write(temp_buffer)?,
+                    Err(value) => {
+                        return Err(
+                            ErrorType::ReadError.err(format!("Unexpected AFI value {value}!"))
+                        )
+                    }
+                }
+            }
+            Self::Role(role) => u8::from(*role).write(temp_buffer)?,
+            Self::AddPath(families) => {
+                for (afi, safi, mode) in families {
+                    match (*afi).into() {
+                        Ok(value) => value.write(temp_buffer)?,
+                        Err(value) => {
+                            return Err(
+                                ErrorType::ReadError.err(format!("Unexpected AFI value {value}!"))
+                            )
+                        }
+                    }
+                    match (*safi).into() {
+                        Ok(value) => value.write(temp_buffer)?,
+                        Err(value) => {
+                            return Err(
+                                ErrorType::ReadError.err(format!("Unexpected SAFI value {value}!"))
+                            )
+                        }
+                    }
+                    u8::from(*mode).write(temp_buffer)?;
+                }
+            }
+            Self::MultipleLabels(families) => {
+                for (afi, safi, count) in families {
+                    match (*afi).into() {
+                        Ok(value) => value.write(temp_buffer)?,
+                        Err(value) => {
+                            return Err(
+                                ErrorType::ReadError.err(format!("Unexpected AFI value {value}!"))
+                            )
+                        }
+                    }
+                    match (*safi).into() {
+                        Ok(value) => value.write(temp_buffer)?,
+                        Err(value) => {
+                            return Err(
+                                ErrorType::ReadError.err(format!("Unexpected SAFI value {value}!"))
+                            )
+                        }
+                    }
+                    count.write(temp_buffer)?;
+                }
+            }
             #[cfg(feature = "bgp_route_refresh")]
             Self::EnhancedRouteRefresh => {}
             Self::LongLivedGracefulRestart => {}
@@ -151,7 +227,39 @@ impl WriteRead for Capability {
             }
             #[cfg(feature = "bgp_route_refresh")]
             2 => Ok(Self::RouteRefresh),
+            7 => {
+                let flags = u8::read(buffer)?;
+                let direction = if flags & 0b00001000 != 0 {
+                    BGPsecDirection::Send
+                } else {
+                    BGPsecDirection::Receive
+                };
+                Ok(Self::BGPsec(flags >> 4, direction, AFI::from(u16::read(buffer)?)))
+            }
+            8 => {
+                let mut families = Vec::new();
+                while buffer.remaining() > 0 {
+                    families.push((
+                        AFI::from(u16::read(buffer)?),
+                        SAFI::from(u8::read(buffer)?),
+                        u8::read(buffer)?,
+                    ));
+                }
+                Ok(Self::MultipleLabels(families))
+            }
+            9 => Ok(Self::Role(Role::from(u8::read(buffer)?))),
             65 => Ok(Self::FourOctetASNumberSupport(u64::read(buffer)?)),
+            69 => {
+                let mut families = Vec::new();
+                while buffer.remaining() > 0 {
+                    families.push((
+                        AFI::from(u16::read(buffer)?),
+                        SAFI::from(u8::read(buffer)?),
+                        AddPathMode::from(u8::read(buffer)?),
+                    ));
+                }
+                Ok(Self::AddPath(families))
+            }
             #[cfg(feature = "bgp_route_refresh")]
             70 => Ok(Self::EnhancedRouteRefresh),
             71 => Ok(Self::LongLivedGracefulRestart),
@@ -167,7 +275,11 @@ impl Capability {
             Self::MultiProtocolExtensions(_, _) => Some(1),
             #[cfg(feature = "bgp_route_refresh")]
             Self::RouteRefresh => Some(2),
+            Self::BGPsec(_, _, _) => Some(7),
+            Self::MultipleLabels(_) => Some(8),
+            Self::Role(_) => Some(9),
             Self::FourOctetASNumberSupport(_) => Some(65),
+            Self::AddPath(_) => Some(69),
             #[cfg(feature = "bgp_route_refresh")]
             Self::EnhancedRouteRefresh => Some(70),
             Self::LongLivedGracefulRestart => Some(71),
@@ -176,6 +288,152 @@ impl Capability {
     }
 }
 
+/// This is the representation of the direction of the BGPsec capability. A router, that announces
+/// the Send direction, is able to send BGPsec updates and a router, that announces the Receive
+/// direction, is able to receive and validate BGPsec updates.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub enum BGPsecDirection {
+    Receive,
+    Send
+}
+
+/// This is the representation of the direction of the ADD-PATH capability for a address family, as
+/// defined in [RFC7911, Section 4](https://www.rfc-editor.org/rfc/rfc7911#section-4).
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub enum AddPathMode {
+    /// The router is able to receive multiple paths from the peer.
+    Receive,
+    /// The router is able to send multiple paths to the peer.
+    Send,
+    Both,
+    Unknown(u8)
+}
+
+impl AddPathMode {
+
+    pub fn can_receive(&self) -> bool {
+        matches!(self, Self::Receive | Self::Both)
+    }
+
+    pub fn can_send(&self) -> bool {
+        matches!(self, Self::Send | Self::Both)
+    }
+
+}
+
+impl From<u8> for AddPathMode {
+    fn from(value: u8) -> Self {
+        match value {
+            1 => Self::Receive,
+            2 => Self::Send,
+            3 => Self::Both,
+            _ => Self::Unknown(value)
+        }
+    }
+}
+
+impl From<AddPathMode> for u8 {
+    fn from(value: AddPathMode) -> Self {
+        match value {
+            AddPathMode::Receive => 1,
+            AddPathMode::Send => 2,
+            AddPathMode::Both => 3,
+            AddPathMode::Unknown(value) => value
+        }
+    }
+}
+
+/// This is the representation of the role of a router in the peering relationship, which is
+/// announced with the Role capability, as defined in
+/// [RFC9234, Section 4.1](https://www.rfc-editor.org/rfc/rfc9234#section-4.1). The role is used
+/// to detect route leaks with the Only to Customer (OTC) attribute.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub enum Role {
+    /// The router is the transit provider of the peer.
+    Provider,
+    /// The router is a route server, which is connected to a route server client.
+    RouteServer,
+    /// The router is a client of the route server.
+    RouteServerClient,
+    /// The router is the customer of the transit provider.
+    Customer,
+    /// The router exchanges routes of its customers with a lateral peer.
+    Peer,
+    Unknown(u8)
+}
+
+impl Role {
+
+    /// This function returns the only role, which is allowed for the peer of a router with this
+    /// role, as defined in [RFC9234, Section 4.2](https://www.rfc-editor.org/rfc/rfc9234#section-4.2).
+    pub fn peer_role(&self) -> Option<Role> {
+        match self {
+            Self::Provider => Some(Self::Customer),
+            Self::RouteServer => Some(Self::RouteServerClient),
+            Self::RouteServerClient => Some(Self::RouteServer),
+            Self::Customer => Some(Self::Provider),
+            Self::Peer => Some(Self::Peer),
+            Self::Unknown(_) => None
+        }
+    }
+
+    /// This function checks the Role capabilities received from the peer against the local role, as
+    /// described in [RFC9234, Section 4.2](https://www.rfc-editor.org/rfc/rfc9234#section-4.2). A
+    /// Role Mismatch error is returned, if the peer announced a role, that doesn't correspond to the
+    /// local role, or multiple different roles. In the strict mode, the Role capability is required
+    /// and a missing capability is treated as a Role Mismatch error too.
+    pub fn validate_peer(&self, capabilities: &[Capability], strict: bool) -> crate::Result<()> {
+        let mismatch = |message: String| ErrorType::BGPError(BGPError::open(OpenMessageError::RoleMismatch))
+            .err(message);
+
+        let mut roles = capabilities.iter().filter_map(|capability| match capability {
+            Capability::Role(role) => Some(*role),
+            _ => None
+        });
+        let Some(role) = roles.next() else {
+            if strict {
+                return Err(mismatch(format!("Expected Role capability with role {:?}, but the peer announced no role", self.peer_role())));
+            }
+            return Ok(());
+        };
+
+        if roles.any(|other| other != role) {
+            return Err(mismatch(String::from("Peer announced multiple different roles")));
+        }
+        if self.peer_role() != Some(role) {
+            return Err(mismatch(format!("Unexpected role {role:?} of the peer for local role {self:?}")));
+        }
+        Ok(())
+    }
+
+}
+
+impl From<u8> for Role {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => Self::Provider,
+            1 => Self::RouteServer,
+            2 => Self::RouteServerClient,
+            3 => Self::Customer,
+            4 => Self::Peer,
+            _ => Self::Unknown(value)
+        }
+    }
+}
+
+impl From<Role> for u8 {
+    fn from(value: Role) -> Self {
+        match value {
+            Role::Provider => 0,
+            Role::RouteServer => 1,
+            Role::RouteServerClient => 2,
+            Role::Customer => 3,
+            Role::Peer => 4,
+            Role::Unknown(value) => value
+        }
+    }
+}
+
 /// This is the representation of the AFI (Address Family Indicator). This representation is used in
 /// the Multi-protocol extensions of the BGP protocol. This value is sent in the Multi-protocol
 /// extensions support capability to tell. the remote peer what specified address family the local
@@ -192,6 +450,11 @@ pub enum AFI {
     /// peer, that the local router is able to transport IPv6 routes.
     IPv6,
 
+    /// This is the value for BGP-LS (Link State) information, as defined in
+    /// [RFC9552, Section 5.2](https://www.rfc-editor.org/rfc/rfc9552#section-5.2). This
+    /// information tells your peer, that the local router is able to transport the topology of IGPs.
+    LinkState,
+
     /// This is only the representation for a unexpected value
     Unexpected(u16),
 }
@@ -201,6 +464,7 @@ impl From<u16> for AFI {
         match value {
             1 => Self::IPv4,
             2 => Self::IPv6,
+            16388 => Self::LinkState,
             value => Self::Unexpected(value),
         }
     }
@@ -212,6 +476,7 @@ impl Into<Result<u16, u16>> for AFI {
         match self {
             Self::IPv4 => Ok(1),
             Self::IPv6 => Ok(2),
+            Self::LinkState => Ok(16388),
             Self::Unexpected(value) => Err(value),
         }
     }
@@ -231,6 +496,8 @@ pub enum SAFI {
     LabeledUnicast,
     NG_MVPN,
     MDT,
+    LinkState,
+    LinkStateVPN,
     VPN,
     VPNMulticast,
     RouteTargetConstrain,
@@ -246,6 +513,8 @@ impl From<u8> for SAFI {
             4 => Self::LabeledUnicast,
             5 => Self::NG_MVPN,
             66 => Self::MDT,
+            71 => Self::LinkState,
+            72 => Self::LinkStateVPN,
             128 => Self::VPN,
             129 => Self::VPNMulticast,
             132 => Self::RouteTargetConstrain,
@@ -264,6 +533,8 @@ impl Into<Result<u8, u8>> for SAFI {
             Self::LabeledUnicast => Ok(4),
             Self::NG_MVPN => Ok(5),
             Self::MDT => Ok(66),
+            Self::LinkState => Ok(71),
+            Self::LinkStateVPN => Ok(72),
             Self::VPN => Ok(128),
             Self::VPNMulticast => Ok(129),
             Self::RouteTargetConstrain => Ok(132),
@@ -0,0 +1,300 @@
+use crate::bgp::error::{BGPError, UpdateMessageError};
+use crate::bgp::opt_params::{AFI, SAFI};
+use crate::bgp::path_attr::{ASPath, ASPathSegment};
+use crate::error::{Error, ErrorType};
+use crate::if_no_std;
+use crate::io::{Buffer, ByteOrder, WriteRead};
+use crate::Result;
+
+if_no_std! {
+    use alloc::{format, vec, vec::Vec};
+}
+
+/// This is the identifier of the only algorithm suite, which is currently defined for BGPsec. The
+/// suite uses SHA-256 and ECDSA with the curve P-256, as defined in
+/// [RFC8208, Section 2](https://www.rfc-editor.org/rfc/rfc8208#section-2).
+pub const ALGORITHM_SUITE_P256_SHA256: u8 = 1;
+
+/// This is the representation of the BGPsec_PATH attribute, as defined in
+/// [RFC8205, Section 3](https://www.rfc-editor.org/rfc/rfc8205#section-3). The attribute replaces
+/// the AS_PATH attribute between BGPsec speakers. The segments of the Secure_Path and the
+/// Signature_Blocks are ordered from the most recent AS to the origin AS.
+#[derive(Clone, PartialEq, Eq, Debug, Hash)]
+pub struct BGPsecPath {
+    pub secure_path: Vec<SecurePathSegment>,
+    pub signature_blocks: Vec<SignatureBlock>
+}
+
+impl WriteRead for BGPsecPath {
+    fn write(&self, buffer: &mut Buffer) -> Result<()> {
+        ((self.secure_path.len() * 6 + 2) as u16).write(buffer)?;
+        for segment in &self.secure_path {
+            segment.write(buffer)?;
+        }
+
+        for block in &self.signature_blocks {
+            let temp_buffer = &mut Buffer::empty(ByteOrder::BigEndian);
+            block.algorithm_suite.write(temp_buffer)?;
+            for segment in &block.segments {
+                segment.write(temp_buffer)?;
+            }
+
+            if temp_buffer.len() + 2 > u16::MAX as usize {
+                return Err(ErrorType::WriteError.err(format!("Unable to write Signature_Block! The block is {} bytes long", temp_buffer.len() + 2)));
+            }
+            ((temp_buffer.len() + 2) as u16).write(buffer)?;
+            temp_buffer.write_buffer(buffer)?;
+        }
+        Ok(())
+    }
+
+    fn read(buffer: &mut Buffer) -> Result<Self> where Self: Sized {
+        let length = u16::read(buffer)? as usize;
+        if length < 8 || !(length - 2).is_multiple_of(6) {
+            return Err(malformed(&format!("Unexpected Secure_Path length {length}")));
+        }
+
+        let mut secure_path = Vec::new();
+        for _ in 0..(length - 2) / 6 {
+            secure_path.push(SecurePathSegment::read(buffer)?);
+        }
+
+        // RFC8205, Section 5.2: One or two Signature_Blocks with one Signature Segment for each
+        // Secure_Path Segment are expected
+        let mut signature_blocks = Vec::new();
+        while buffer.remaining() > 0 {
+            let length = u16::read(buffer)? as usize;
+            if length < 3 {
+                return Err(malformed(&format!("Unexpected Signature_Block length {length}")));
+            }
+            let block_buffer = &mut Buffer::read_buffer(buffer, length - 2)?;
+            let algorithm_suite = u8::read(block_buffer)?;
+
+            let mut segments = Vec::new();
+            while block_buffer.remaining() > 0 {
+                segments.push(SignatureSegment::read(block_buffer)?);
+            }
+
+            if segments.len() != secure_path.len() {
+                return Err(malformed(&format!("Expected {} Signature Segments, but got {}", secure_path.len(), segments.len())));
+            }
+            signature_blocks.push(SignatureBlock {
+                algorithm_suite,
+                segments
+            });
+        }
+
+        if signature_blocks.is_empty() || signature_blocks.len() > 2 {
+            return Err(malformed(&format!("Expected one or two Signature_Blocks, but got {}", signature_blocks.len())));
+        }
+        Ok(Self {
+            secure_path,
+            signature_blocks
+        })
+    }
+}
+
+impl BGPsecPath {
+
+    /// This function converts the Secure_Path into a AS_PATH for peers without support of BGPsec, as
+    /// described in [RFC8205, Section 4.4](https://www.rfc-editor.org/rfc/rfc8205#section-4.4).
+    /// The AS number of each segment is repeated pCount times and the segments with the
+    /// Confed_Segment flag are converted into AS_CONFED_SEQUENCE segments.
+    pub fn to_as_path(&self) -> ASPath {
+        ASPath::new(self.secure_path.iter().map(|segment| {
+            let values = vec![segment.autonomous_system; segment.pcount as usize];
+            if segment.is_confed_segment() {
+                ASPathSegment::ASConfedSequence(values)
+            } else {
+                ASPathSegment::ASSequence(values)
+            }
+        }).collect())
+    }
+
+    /// This function returns the sequence of octets, which is hashed and signed by the AS of the
+    /// Secure_Path Segment at the index, as described in
+    /// [RFC8205, Section 4.2](https://www.rfc-editor.org/rfc/rfc8205#section-4.2). The target AS
+    /// is the AS, to which the AS at the index sent the update. The NLRI is the prefix of the update
+    /// encoded with the length of the prefix and the significant bytes of the prefix.
+    pub fn signed_data(&self, block: &SignatureBlock, index: usize, target_as: u32, afi: AFI, safi: SAFI, nlri: &[u8]) -> Result<Vec<u8>> {
+        if index >= self.secure_path.len() || block.segments.len() != self.secure_path.len() {
+            return Err(ErrorType::OtherError.err(format!("Unable to find the Signature Segment {index} of the path with {} segments", self.secure_path.len())));
+        }
+
+        let buffer = &mut Buffer::empty(ByteOrder::BigEndian);
+        target_as.write(buffer)?;
+        for position in index..self.secure_path.len() - 1 {
+            block.segments[position + 1].write(buffer)?;
+            self.secure_path[position].write(buffer)?;
+        }
+        self.secure_path[self.secure_path.len() - 1].write(buffer)?;
+
+        block.algorithm_suite.write(buffer)?;
+        let afi: core::result::Result<u16, u16> = afi.into();
+        afi.unwrap_or_else(|value| value).write(buffer)?;
+        let safi: core::result::Result<u8, u8> = safi.into();
+        safi.unwrap_or_else(|value| value).write(buffer)?;
+        buffer.write_bytes_slice(nlri);
+        Ok(buffer.bytes.clone())
+    }
+
+    /// This function validates the signatures of the Signature_Block with the supported algorithm
+    /// suite with the router keys, as described in
+    /// [RFC8205, Section 5.2](https://www.rfc-editor.org/rfc/rfc8205#section-5.2). The target AS
+    /// is the AS of the validating router. The router keys are supplied by the caller, for example
+    /// from the router certificates of the RPKI.
+    #[cfg(feature = "bgpsec")]
+    pub fn verify(&self, target_as: u32, afi: AFI, safi: SAFI, nlri: &[u8], keys: &[RouterKey]) -> BGPsecValidation {
+        use p256::ecdsa::signature::Verifier;
+        use p256::ecdsa::{Signature, VerifyingKey};
+
+        let Some(block) = self.signature_blocks.iter().find(|block| block.algorithm_suite == ALGORITHM_SUITE_P256_SHA256) else {
+            return BGPsecValidation::UnsupportedAlgorithmSuite;
+        };
+        if self.secure_path.is_empty() || block.segments.len() != self.secure_path.len() {
+            return BGPsecValidation::Malformed;
+        }
+
+        for (index, (path_segment, signature_segment)) in self.secure_path.iter().zip(&block.segments).enumerate() {
+            let segment_target = match index {
+                0 => target_as,
+                _ => self.secure_path[index - 1].autonomous_system
+            };
+            let Ok(data) = self.signed_data(block, index, segment_target, afi, safi, nlri) else {
+                return BGPsecValidation::Malformed;
+            };
+            let Ok(signature) = Signature::from_der(&signature_segment.signature) else {
+                return BGPsecValidation::InvalidSignature(path_segment.autonomous_system);
+            };
+
+            let mut keys = keys.iter()
+                .filter(|key| key.autonomous_system == path_segment.autonomous_system && key.subject_key_identifier == signature_segment.subject_key_identifier)
+                .peekable();
+            if keys.peek().is_none() {
+                return BGPsecValidation::MissingRouterKey(path_segment.autonomous_system);
+            }
+
+            let valid = keys.any(|key| VerifyingKey::from_sec1_bytes(&key.public_key)
+                .map(|key| key.verify(&data, &signature).is_ok())
+                .unwrap_or(false));
+            if !valid {
+                return BGPsecValidation::InvalidSignature(path_segment.autonomous_system);
+            }
+        }
+        BGPsecValidation::Valid
+    }
+
+}
+
+/// This is the representation of a Secure_Path Segment with the number of prepends of the AS, the
+/// flags and the AS number. The flags are preserved as raw byte, because they are signed.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub struct SecurePathSegment {
+    pub pcount: u8,
+    pub flags: u8,
+    pub autonomous_system: u32
+}
+
+impl SecurePathSegment {
+
+    /// The segment was added by a member AS of a confederation.
+    pub const CONFED_SEGMENT: u8 = 0b10000000;
+
+    pub fn is_confed_segment(&self) -> bool {
+        self.flags & Self::CONFED_SEGMENT != 0
+    }
+
+}
+
+impl WriteRead for SecurePathSegment {
+    fn write(&self, buffer: &mut Buffer) -> Result<()> {
+        self.pcount.write(buffer)?;
+        self.flags.write(buffer)?;
+        self.autonomous_system.write(buffer)
+    }
+
+    fn read(buffer: &mut Buffer) -> Result<Self> where Self: Sized {
+        Ok(Self {
+            pcount: u8::read(buffer)?,
+            flags: u8::read(buffer)?,
+            autonomous_system: u32::read(buffer)?
+        })
+    }
+}
+
+/// This is the representation of a Signature_Block with the algorithm suite of the signatures and
+/// one Signature Segment for each Secure_Path Segment.
+#[derive(Clone, PartialEq, Eq, Debug, Hash)]
+pub struct SignatureBlock {
+    pub algorithm_suite: u8,
+    pub segments: Vec<SignatureSegment>
+}
+
+/// This is the representation of a Signature Segment with the Subject Key Identifier of the router
+/// key and the signature, which is DER-encoded for the algorithm suite 1.
+#[derive(Clone, PartialEq, Eq, Debug, Hash)]
+pub struct SignatureSegment {
+    pub subject_key_identifier: [u8; 20],
+    pub signature: Vec<u8>
+}
+
+impl WriteRead for SignatureSegment {
+    fn write(&self, buffer: &mut Buffer) -> Result<()> {
+        if self.signature.len() > u16::MAX as usize {
+            return Err(ErrorType::WriteError.err(format!("Unable to write signature! The signature is {} bytes long", self.signature.len())));
+        }
+        buffer.write_bytes_array(self.subject_key_identifier);
+        (self.signature.len() as u16).write(buffer)?;
+        buffer.write_bytes_vector(&self.signature);
+        Ok(())
+    }
+
+    fn read(buffer: &mut Buffer) -> Result<Self> where Self: Sized {
+        let subject_key_identifier = buffer.read_bytes_array::<20>()?;
+        let length = u16::read(buffer)? as usize;
+        Ok(Self {
+            subject_key_identifier,
+            signature: buffer.read_bytes_vector(length)?
+        })
+    }
+}
+
+/// This is the representation of a router key, which is used to validate the signatures of the AS.
+/// The public key is a SEC1-encoded point of the curve P-256.
+#[cfg(feature = "bgpsec")]
+#[derive(Clone, PartialEq, Eq, Debug, Hash)]
+pub struct RouterKey {
+    pub autonomous_system: u32,
+    pub subject_key_identifier: [u8; 20],
+    pub public_key: Vec<u8>
+}
+
+/// This is the representation of the result of the validation of a BGPsec_PATH attribute. Only
+/// Valid routes are considered as valid BGPsec routes, all other states are Not Valid.
+#[cfg(feature = "bgpsec")]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub enum BGPsecValidation {
+    Valid,
+    /// The attribute doesn't contain a Signature_Block with a supported algorithm suite.
+    UnsupportedAlgorithmSuite,
+    /// No router key was supplied for the AS and the Subject Key Identifier of the segment.
+    MissingRouterKey(u32),
+    /// The signature of the AS is invalid.
+    InvalidSignature(u32),
+    /// The number of the Signature Segments doesn't match the number of Secure_Path Segments.
+    Malformed
+}
+
+#[cfg(feature = "bgpsec")]
+impl BGPsecValidation {
+
+    pub fn is_valid(&self) -> bool {
+        *self == Self::Valid
+    }
+
+}
+
+fn malformed(message: &str) -> Error {
+    ErrorType::BGPError(BGPError::update(UpdateMessageError::MalformedASPath))
+        .err(format!("Malformed BGPsec_PATH attribute! {message}"))
+}
@@ -0,0 +1,668 @@
+use crate::bgp::error::{BGPError, UpdateMessageError};
+use crate::error::{Error, ErrorType};
+use crate::if_no_std;
+use crate::io::{Buffer, ByteOrder, WriteRead};
+use crate::Result;
+use core::net::{Ipv4Addr, Ipv6Addr};
+
+if_no_std! {
+    use alloc::{format, string::String, vec::Vec};
+}
+
+/// This is the representation of a BGP-LS NLRI, as defined in
+/// [RFC9552, Section 5.2](https://www.rfc-editor.org/rfc/rfc9552#section-5.2). The NLRI
+/// identifies a node, a link or a prefix of the topology of a IGP with the protocol, the
+/// identifier of the routing universe and the descriptors.
+#[derive(Clone, PartialEq, Eq, Debug, Hash)]
+pub enum LinkStateNLRI {
+    /// This NLRI identifies a node by the descriptors of the node (Type 1).
+    Node {
+        protocol: LinkStateProtocol,
+        identifier: u64,
+        local_node: Vec<NodeDescriptor>
+    },
+    /// This NLRI identifies a link by the descriptors of both nodes of the link and the link
+    /// descriptors (Type 2).
+    Link {
+        protocol: LinkStateProtocol,
+        identifier: u64,
+        local_node: Vec<NodeDescriptor>,
+        remote_node: Vec<NodeDescriptor>,
+        link: Vec<LinkDescriptor>
+    },
+    /// This NLRI identifies a IPv4 prefix, which is announced by the node (Type 3).
+    IPv4Prefix {
+        protocol: LinkStateProtocol,
+        identifier: u64,
+        local_node: Vec<NodeDescriptor>,
+        prefix: Vec<PrefixDescriptor>
+    },
+    /// This NLRI identifies a IPv6 prefix, which is announced by the node (Type 4).
+    IPv6Prefix {
+        protocol: LinkStateProtocol,
+        identifier: u64,
+        local_node: Vec<NodeDescriptor>,
+        prefix: Vec<PrefixDescriptor>
+    },
+    Unknown(u16, Vec<u8>)
+}
+
+impl WriteRead for LinkStateNLRI {
+    fn write(&self, buffer: &mut Buffer) -> Result<()> {
+        let value = &mut Buffer::empty(ByteOrder::BigEndian);
+        let ty = match self {
+            Self::Node { protocol, identifier, local_node } => {
+                u8::from(*protocol).write(value)?;
+                identifier.write(value)?;
+                write_tlv(value, 256, &write_all(local_node)?)?;
+                1
+            },
+            Self::Link { protocol, identifier, local_node, remote_node, link } => {
+                u8::from(*protocol).write(value)?;
+                identifier.write(value)?;
+                write_tlv(value, 256, &write_all(local_node)?)?;
+                write_tlv(value, 257, &write_all(remote_node)?)?;
+                write_all(link)?.write_buffer(value)?;
+                2
+            },
+            Self::IPv4Prefix { protocol, identifier, local_node, prefix } | Self::IPv6Prefix { protocol, identifier, local_node, prefix } => {
+                u8::from(*protocol).write(value)?;
+                identifier.write(value)?;
+                write_tlv(value, 256, &write_all(local_node)?)?;
+                write_all(prefix)?.write_buffer(value)?;
+                if matches!(self, Self::IPv4Prefix { .. }) { 3 } else { 4 }
+            },
+            Self::Unknown(ty, data) => {
+                value.write_bytes_vector(data);
+                *ty
+            }
+        };
+        write_tlv(buffer, ty, value)
+    }
+
+    fn read(buffer: &mut Buffer) -> Result<Self> where Self: Sized {
+        let (ty, value) = &mut read_tlv(buffer)?;
+        if !(1..=4).contains(ty) {
+            return Ok(Self::Unknown(*ty, value.read_bytes_vector(value.len())?));
+        }
+
+        let protocol = LinkStateProtocol::from(u8::read(value)?);
+        let identifier = u64::read(value)?;
+        let local_node = read_node_descriptors(value, 256)?;
+        Ok(match ty {
+            1 => Self::Node {
+                protocol,
+                identifier,
+                local_node
+            },
+            2 => Self::Link {
+                protocol,
+                identifier,
+                local_node,
+                remote_node: read_node_descriptors(value, 257)?,
+                link: read_all(value)?
+            },
+            3 => Self::IPv4Prefix {
+                protocol,
+                identifier,
+                local_node,
+                prefix: read_all(value)?
+            },
+            _ => Self::IPv6Prefix {
+                protocol,
+                identifier,
+                local_node,
+                prefix: read_all(value)?
+            }
+        })
+    }
+}
+
+/// This is the representation of the source protocol of the BGP-LS information, as defined in
+/// [RFC9552, Section 5.2](https://www.rfc-editor.org/rfc/rfc9552#section-5.2).
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub enum LinkStateProtocol {
+    ISISLevel1,
+    ISISLevel2,
+    OSPFv2,
+    Direct,
+    Static,
+    OSPFv3,
+    /// This protocol is used for BGP topologies of data centers, as defined in
+    /// [RFC9086](https://www.rfc-editor.org/rfc/rfc9086).
+    BGP,
+    Unknown(u8)
+}
+
+impl From<u8> for LinkStateProtocol {
+    fn from(value: u8) -> Self {
+        match value {
+            1 => Self::ISISLevel1,
+            2 => Self::ISISLevel2,
+            3 => Self::OSPFv2,
+            4 => Self::Direct,
+            5 => Self::Static,
+            6 => Self::OSPFv3,
+            7 => Self::BGP,
+            _ => Self::Unknown(value)
+        }
+    }
+}
+
+impl From<LinkStateProtocol> for u8 {
+    fn from(value: LinkStateProtocol) -> Self {
+        match value {
+            LinkStateProtocol::ISISLevel1 => 1,
+            LinkStateProtocol::ISISLevel2 => 2,
+            LinkStateProtocol::OSPFv2 => 3,
+            LinkStateProtocol::Direct => 4,
+            LinkStateProtocol::Static => 5,
+            LinkStateProtocol::OSPFv3 => 6,
+            LinkStateProtocol::BGP => 7,
+            LinkStateProtocol::Unknown(value) => value
+        }
+    }
+}
+
+/// This is the representation of a sub-TLV of the Local and Remote Node Descriptors TLVs, as defined
+/// in [RFC9552, Section 5.2.1.4](https://www.rfc-editor.org/rfc/rfc9552#section-5.2.1.4).
+#[derive(Clone, PartialEq, Eq, Debug, Hash)]
+pub enum NodeDescriptor {
+    /// (Type 512)
+    AutonomousSystem(u32),
+    /// (Type 513)
+    BGPLSIdentifier(u32),
+    /// (Type 514)
+    OSPFAreaId(u32),
+    /// This descriptor contains the IS-IS System-ID or OSPF Router-ID with the optional
+    /// pseudonode identifier or interface address (Type 515).
+    IGPRouterId(Vec<u8>),
+    /// This descriptor is defined in [RFC9086](https://www.rfc-editor.org/rfc/rfc9086) (Type 516).
+    BGPRouterId(Ipv4Addr),
+    /// This descriptor is defined in [RFC9086](https://www.rfc-editor.org/rfc/rfc9086) (Type 517).
+    MemberAutonomousSystem(u32),
+    Unknown(u16, Vec<u8>)
+}
+
+impl WriteRead for NodeDescriptor {
+    fn write(&self, buffer: &mut Buffer) -> Result<()> {
+        let value = &mut Buffer::empty(ByteOrder::BigEndian);
+        let ty = match self {
+            Self::AutonomousSystem(autonomous_system) => {
+                autonomous_system.write(value)?;
+                512
+            },
+            Self::BGPLSIdentifier(identifier) => {
+                identifier.write(value)?;
+                513
+            },
+            Self::OSPFAreaId(area) => {
+                area.write(value)?;
+                514
+            },
+            Self::IGPRouterId(router_id) => {
+                value.write_bytes_vector(router_id);
+                515
+            },
+            Self::BGPRouterId(router_id) => {
+                router_id.write(value)?;
+                516
+            },
+            Self::MemberAutonomousSystem(autonomous_system) => {
+                autonomous_system.write(value)?;
+                517
+            },
+            Self::Unknown(ty, data) => {
+                value.write_bytes_vector(data);
+                *ty
+            }
+        };
+        write_tlv(buffer, ty, value)
+    }
+
+    fn read(buffer: &mut Buffer) -> Result<Self> where Self: Sized {
+        let (ty, value) = &mut read_tlv(buffer)?;
+        let descriptor = match (*ty, value.len()) {
+            (512, 4) => Self::AutonomousSystem(u32::read(value)?),
+            (513, 4) => Self::BGPLSIdentifier(u32::read(value)?),
+            (514, 4) => Self::OSPFAreaId(u32::read(value)?),
+            (515, _) => Self::IGPRouterId(value.read_bytes_vector(value.len())?),
+            (516, 4) => Self::BGPRouterId(Ipv4Addr::read(value)?),
+            (517, 4) => Self::MemberAutonomousSystem(u32::read(value)?),
+            (512..=517, length) => return Err(malformed(&format!("Unexpected length {length} of node descriptor {ty}"))),
+            _ => Self::Unknown(*ty, value.read_bytes_vector(value.len())?)
+        };
+        Ok(descriptor)
+    }
+}
+
+/// This is the representation of a Link Descriptor TLV, as defined in
+/// [RFC9552, Section 5.2.2](https://www.rfc-editor.org/rfc/rfc9552#section-5.2.2).
+#[derive(Clone, PartialEq, Eq, Debug, Hash)]
+pub enum LinkDescriptor {
+    /// This descriptor contains the local and the remote identifier of the link (Type 258).
+    LinkIdentifiers(u32, u32),
+    /// (Type 259)
+    IPv4InterfaceAddress(Ipv4Addr),
+    /// (Type 260)
+    IPv4NeighborAddress(Ipv4Addr),
+    /// (Type 261)
+    IPv6InterfaceAddress(Ipv6Addr),
+    /// (Type 262)
+    IPv6NeighborAddress(Ipv6Addr),
+    /// (Type 263)
+    MultiTopologyId(Vec<u16>),
+    Unknown(u16, Vec<u8>)
+}
+
+impl WriteRead for LinkDescriptor {
+    fn write(&self, buffer: &mut Buffer) -> Result<()> {
+        let value = &mut Buffer::empty(ByteOrder::BigEndian);
+        let ty = match self {
+            Self::LinkIdentifiers(local, remote) => {
+                local.write(value)?;
+                remote.write(value)?;
+                258
+            },
+            Self::IPv4InterfaceAddress(address) => {
+                address.write(value)?;
+                259
+            },
+            Self::IPv4NeighborAddress(address) => {
+                address.write(value)?;
+                260
+            },
+            Self::IPv6InterfaceAddress(address) => {
+                address.write(value)?;
+                261
+            },
+            Self::IPv6NeighborAddress(address) => {
+                address.write(value)?;
+                262
+            },
+            Self::MultiTopologyId(topologies) => {
+                for topology in topologies {
+                    topology.write(value)?;
+                }
+                263
+            },
+            Self::Unknown(ty, data) => {
+                value.write_bytes_vector(data);
+                *ty
+            }
+        };
+        write_tlv(buffer, ty, value)
+    }
+
+    fn read(buffer: &mut Buffer) -> Result<Self> where Self: Sized {
+        let (ty, value) = &mut read_tlv(buffer)?;
+        let descriptor = match (*ty, value.len()) {
+            (258, 8) => Self::LinkIdentifiers(u32::read(value)?, u32::read(value)?),
+            (259, 4) => Self::IPv4InterfaceAddress(Ipv4Addr::read(value)?),
+            (260, 4) => Self::IPv4NeighborAddress(Ipv4Addr::read(value)?),
+            (261, 16) => Self::IPv6InterfaceAddress(Ipv6Addr::read(value)?),
+            (262, 16) => Self::IPv6NeighborAddress(Ipv6Addr::read(value)?),
+            (263, length) if length % 2 == 0 => Self::MultiTopologyId(read_multi_topology(value)?),
+            (258..=263, length) => return Err(malformed(&format!("Unexpected length {length} of link descriptor {ty}"))),
+            _ => Self::Unknown(*ty, value.read_bytes_vector(value.len())?)
+        };
+        Ok(descriptor)
+    }
+}
+
+/// This is the representation of a Prefix Descriptor TLV, as defined in
+/// [RFC9552, Section 5.2.3](https://www.rfc-editor.org/rfc/rfc9552#section-5.2.3).
+#[derive(Clone, PartialEq, Eq, Debug, Hash)]
+pub enum PrefixDescriptor {
+    /// (Type 263)
+    MultiTopologyId(Vec<u16>),
+    /// (Type 264)
+    OSPFRouteType(u8),
+    /// This descriptor contains the length of the prefix and the significant bytes of the
+    /// prefix (Type 265).
+    IPReachability(u8, Vec<u8>),
+    Unknown(u16, Vec<u8>)
+}
+
+impl WriteRead for PrefixDescriptor {
+    fn write(&self, buffer: &mut Buffer) -> Result<()> {
+        let value = &mut Buffer::empty(ByteOrder::BigEndian);
+        let ty = match self {
+            Self::MultiTopologyId(topologies) => {
+                for topology in topologies {
+                    topology.write(value)?;
+                }
+                263
+            },
+            Self::OSPFRouteType(route_type) => {
+                route_type.write(value)?;
+                264
+            },
+            Self::IPReachability(length, prefix) => {
+                length.write(value)?;
+                value.write_bytes_vector(prefix);
+                265
+            },
+            Self::Unknown(ty, data) => {
+                value.write_bytes_vector(data);
+                *ty
+            }
+        };
+        write_tlv(buffer, ty, value)
+    }
+
+    fn read(buffer: &mut Buffer) -> Result<Self> where Self: Sized {
+        let (ty, value) = &mut read_tlv(buffer)?;
+        let descriptor = match (*ty, value.len()) {
+            (263, length) if length % 2 == 0 => Self::MultiTopologyId(read_multi_topology(value)?),
+            (264, 1) => Self::OSPFRouteType(u8::read(value)?),
+            (265, length) if length >= 1 => {
+                let prefix_length = u8::read(value)?;
+                if value.remaining() != (prefix_length as usize).div_ceil(8) {
+                    return Err(malformed(&format!("Unexpected prefix of {} bytes with length {prefix_length}", value.remaining())));
+                }
+                Self::IPReachability(prefix_length, value.read_bytes_vector(value.remaining())?)
+            },
+            (263..=265, length) => return Err(malformed(&format!("Unexpected length {length} of prefix descriptor {ty}"))),
+            _ => Self::Unknown(*ty, value.read_bytes_vector(value.len())?)
+        };
+        Ok(descriptor)
+    }
+}
+
+/// This is the representation of a TLV of the BGP-LS attribute, as defined in
+/// [RFC9552, Section 5.3](https://www.rfc-editor.org/rfc/rfc9552#section-5.3). The TLVs
+/// describe the properties of the node, link or prefix of the NLRI. The Segment Routing TLVs are
+/// defined in [RFC9085](https://www.rfc-editor.org/rfc/rfc9085).
+#[derive(Clone, PartialEq, Eq, Debug, Hash)]
+pub enum LinkStateAttributeTlv {
+    /// (Type 1024)
+    NodeFlags(u8),
+    /// (Type 1026)
+    NodeName(String),
+    /// (Type 1028)
+    LocalIPv4RouterId(Ipv4Addr),
+    /// (Type 1029)
+    LocalIPv6RouterId(Ipv6Addr),
+    /// (Type 1030)
+    RemoteIPv4RouterId(Ipv4Addr),
+    /// (Type 1031)
+    RemoteIPv6RouterId(Ipv6Addr),
+    /// This TLV contains the flags and the ranges of the SRGB of the node (Type 1034).
+    SRCapabilities(u8, Vec<SRCapabilityRange>),
+    /// (Type 1088)
+    AdministrativeGroup(u32),
+    /// (Type 1089)
+    MaximumLinkBandwidth(Bandwidth),
+    /// (Type 1090)
+    MaximumReservableBandwidth(Bandwidth),
+    /// This TLV contains the unreserved bandwidth of the eight priority levels (Type 1091).
+    UnreservedBandwidth([Bandwidth; 8]),
+    /// (Type 1092)
+    TEDefaultMetric(u32),
+    /// This TLV contains the metric and the length of the metric in bytes, which is 1 byte for
+    /// IS-IS narrow metrics, 2 bytes for OSPF and 3 bytes for IS-IS wide metrics (Type 1095).
+    IGPMetric(u32, u8),
+    /// (Type 1152)
+    IGPFlags(u8),
+    /// (Type 1155)
+    PrefixMetric(u32),
+    Unknown(u16, Vec<u8>)
+}
+
+impl WriteRead for LinkStateAttributeTlv {
+    fn write(&self, buffer: &mut Buffer) -> Result<()> {
+        let value = &mut Buffer::empty(ByteOrder::BigEndian);
+        let ty = match self {
+            Self::NodeFlags(flags) => {
+                flags.write(value)?;
+                1024
+            },
+            Self::NodeName(name) => {
+                value.write_bytes_slice(name.as_bytes());
+                1026
+            },
+            Self::LocalIPv4RouterId(router_id) => {
+                router_id.write(value)?;
+                1028
+            },
+            Self::LocalIPv6RouterId(router_id) => {
+                router_id.write(value)?;
+                1029
+            },
+            Self::RemoteIPv4RouterId(router_id) => {
+                router_id.write(value)?;
+                1030
+            },
+            Self::RemoteIPv6RouterId(router_id) => {
+                router_id.write(value)?;
+                1031
+            },
+            Self::SRCapabilities(flags, ranges) => {
+                flags.write(value)?;
+                0u8.write(value)?;
+                for range in ranges {
+                    range.write(value)?;
+                }
+                1034
+            },
+            Self::AdministrativeGroup(group) => {
+                group.write(value)?;
+                1088
+            },
+            Self::MaximumLinkBandwidth(bandwidth) => {
+                bandwidth.0.write(value)?;
+                1089
+            },
+            Self::MaximumReservableBandwidth(bandwidth) => {
+                bandwidth.0.write(value)?;
+                1090
+            },
+            Self::UnreservedBandwidth(bandwidths) => {
+                for bandwidth in bandwidths {
+                    bandwidth.0.write(value)?;
+                }
+                1091
+            },
+            Self::TEDefaultMetric(metric) => {
+                metric.write(value)?;
+                1092
+            },
+            Self::IGPMetric(metric, length) => {
+                if !(1..=3).contains(length) || (*metric >> (*length * 8)) != 0 {
+                    return Err(ErrorType::WriteError.err(format!("Unable to write IGP metric {metric} with {length} bytes!")));
+                }
+                value.write_bytes_slice(&metric.to_be_bytes()[4 - *length as usize..]);
+                1095
+            },
+            Self::IGPFlags(flags) => {
+                flags.write(value)?;
+                1152
+            },
+            Self::PrefixMetric(metric) => {
+                metric.write(value)?;
+                1155
+            },
+            Self::Unknown(ty, data) => {
+                value.write_bytes_vector(data);
+                *ty
+            }
+        };
+        write_tlv(buffer, ty, value)
+    }
+
+    fn read(buffer: &mut Buffer) -> Result<Self> where Self: Sized {
+        let (ty, value) = &mut read_tlv(buffer)?;
+        let tlv = match (*ty, value.len()) {
+            (1024, 1) => Self::NodeFlags(u8::read(value)?),
+            (1026, length) => Self::NodeName(String::from_utf8(value.read_bytes_vector(length)?)
+                .map_err(|_| malformed("Node name isn't valid UTF-8"))?),
+            (1028, 4) => Self::LocalIPv4RouterId(Ipv4Addr::read(value)?),
+            (1029, 16) => Self::LocalIPv6RouterId(Ipv6Addr::read(value)?),
+            (1030, 4) => Self::RemoteIPv4RouterId(Ipv4Addr::read(value)?),
+            (1031, 16) => Self::RemoteIPv6RouterId(Ipv6Addr::read(value)?),
+            (1034, length) if length >= 2 => {
+                let flags = u8::read(value)?;
+                u8::read(value)?;
+                let mut ranges = Vec::new();
+                while value.remaining() > 0 {
+                    ranges.push(SRCapabilityRange::read(value)?);
+                }
+                Self::SRCapabilities(flags, ranges)
+            },
+            (1088, 4) => Self::AdministrativeGroup(u32::read(value)?),
+            (1089, 4) => Self::MaximumLinkBandwidth(Bandwidth(u32::read(value)?)),
+            (1090, 4) => Self::MaximumReservableBandwidth(Bandwidth(u32::read(value)?)),
+            (1091, 32) => {
+                let mut bandwidths = [Bandwidth(0); 8];
+                for bandwidth in bandwidths.iter_mut() {
+                    *bandwidth = Bandwidth(u32::read(value)?);
+                }
+                Self::UnreservedBandwidth(bandwidths)
+            },
+            (1092, 4) => Self::TEDefaultMetric(u32::read(value)?),
+            (1095, length @ 1..=3) => {
+                let mut bytes = [0; 4];
+                bytes[4 - length..].copy_from_slice(&value.read_bytes_vector(length)?);
+                Self::IGPMetric(u32::from_be_bytes(bytes), length as u8)
+            },
+            (1152, 1) => Self::IGPFlags(u8::read(value)?),
+            (1155, 4) => Self::PrefixMetric(u32::read(value)?),
+            (1024 | 1028..=1031 | 1034 | 1088..=1092 | 1095 | 1152 | 1155, length) => {
+                return Err(malformed(&format!("Unexpected length {length} of attribute TLV {ty}")))
+            },
+            _ => Self::Unknown(*ty, value.read_bytes_vector(value.len())?)
+        };
+        Ok(tlv)
+    }
+}
+
+/// This is the representation of a bandwidth of the BGP-LS attribute, which is encoded as 32-bit
+/// IEEE floating point number in bytes per second. The raw bits are stored to keep the
+/// representation comparable.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub struct Bandwidth(pub u32);
+
+impl Bandwidth {
+
+    pub fn from_bytes_per_second(bandwidth: f32) -> Self {
+        Self(bandwidth.to_bits())
+    }
+
+    pub fn bytes_per_second(&self) -> f32 {
+        f32::from_bits(self.0)
+    }
+
+}
+
+/// This is the representation of a range of the SR Capabilities TLV, as defined in
+/// [RFC9085, Section 2.1.2](https://www.rfc-editor.org/rfc/rfc9085#section-2.1.2). The range
+/// starts with the first SID, which is a label or an index.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub struct SRCapabilityRange {
+    pub range_size: u32,
+    pub first_sid: SIDLabel
+}
+
+impl WriteRead for SRCapabilityRange {
+    fn write(&self, buffer: &mut Buffer) -> Result<()> {
+        if self.range_size > 0xFFFFFF {
+            return Err(ErrorType::WriteError.err(format!("Unable to write range size {}! The size is limited to 3 bytes", self.range_size)));
+        }
+        buffer.write_bytes_slice(&self.range_size.to_be_bytes()[1..]);
+        1161u16.write(buffer)?;
+        match self.first_sid {
+            SIDLabel::Label(label) => {
+                if label > 0xFFFFF {
+                    return Err(ErrorType::WriteError.err(format!("Unable to write label {label}! The label is limited to 20 bits")));
+                }
+                3u16.write(buffer)?;
+                buffer.write_bytes_slice(&label.to_be_bytes()[1..]);
+            },
+            SIDLabel::Index(index) => {
+                4u16.write(buffer)?;
+                index.write(buffer)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn read(buffer: &mut Buffer) -> Result<Self> where Self: Sized {
+        let [a, b, c] = buffer.read_bytes_array::<3>()?;
+        let (ty, value) = &mut read_tlv(buffer)?;
+        let first_sid = match (*ty, value.len()) {
+            (1161, 3) => {
+                let [a, b, c] = value.read_bytes_array::<3>()?;
+                SIDLabel::Label(u32::from_be_bytes([0, a, b, c]) & 0xFFFFF)
+            },
+            (1161, 4) => SIDLabel::Index(u32::read(value)?),
+            (ty, length) => return Err(malformed(&format!("Expected SID/Label sub-TLV, but got type {ty} with {length} bytes")))
+        };
+        Ok(Self {
+            range_size: u32::from_be_bytes([0, a, b, c]),
+            first_sid
+        })
+    }
+}
+
+/// This is the representation of the SID/Label sub-TLV, as defined in
+/// [RFC9085, Section 2.1.1](https://www.rfc-editor.org/rfc/rfc9085#section-2.1.1).
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub enum SIDLabel {
+    /// The 20-bit MPLS label, which is encoded in 3 bytes.
+    Label(u32),
+    /// The 4-byte index into the SRGB.
+    Index(u32)
+}
+
+fn read_node_descriptors(buffer: &mut Buffer, expected: u16) -> Result<Vec<NodeDescriptor>> {
+    let (ty, value) = &mut read_tlv(buffer)?;
+    if *ty != expected {
+        return Err(malformed(&format!("Expected node descriptors TLV {expected}, but got TLV {ty}")));
+    }
+    read_all(value)
+}
+
+fn read_multi_topology(buffer: &mut Buffer) -> Result<Vec<u16>> {
+    let mut topologies = Vec::new();
+    while buffer.remaining() > 0 {
+        topologies.push(u16::read(buffer)?);
+    }
+    Ok(topologies)
+}
+
+fn write_all<T: WriteRead>(values: &[T]) -> Result<Buffer> {
+    let mut buffer = Buffer::empty(ByteOrder::BigEndian);
+    for value in values {
+        value.write(&mut buffer)?;
+    }
+    Ok(buffer)
+}
+
+fn read_all<T: WriteRead>(buffer: &mut Buffer) -> Result<Vec<T>> {
+    let mut values = Vec::new();
+    while buffer.remaining() > 0 {
+        values.push(T::read(buffer)?);
+    }
+    Ok(values)
+}
+
+fn write_tlv(buffer: &mut Buffer, ty: u16, value: &Buffer) -> Result<()> {
+    if value.len() > u16::MAX as usize {
+        return Err(ErrorType::WriteError.err(format!("Unable to write BGP-LS TLV {ty}! The value is {} bytes long", value.len())));
+    }
+    ty.write(buffer)?;
+    (value.len() as u16).write(buffer)?;
+    value.write_buffer(buffer)
+}
+
+fn read_tlv(buffer: &mut Buffer) -> Result<(u16, Buffer)> {
+    let ty = u16::read(buffer)?;
+    let length = u16::read(buffer)? as usize;
+    Ok((ty, Buffer::read_buffer(buffer, length)?))
+}
+
+fn malformed(message: &str) -> Error {
+    ErrorType::BGPError(BGPError::update(UpdateMessageError::OptionalAttributeError))
+        .err(format!("Malformed BGP-LS information! {message}"))
+}
@@ -0,0 +1,2235 @@
+use bitflags::bitflags;
+use crate::bgp::path_attr::bgpsec::BGPsecPath;
+use crate::bgp::path_attr::link_state::LinkStateAttributeTlv;
+use crate::bgp::path_attr::pmsi_tunnel::PMSITunnel;
+use crate::bgp::path_attr::prefix_sid::PrefixSIDTlv;
+use crate::bgp::path_attr::tunnel_encap::TunnelTlv;
+use crate::bgp::error::{BGPError, ErrorAction, UpdateDiagnostic, UpdateMessageError};
+use crate::bgp::opt_params::Role;
+use crate::bgp::SessionParameters;
+use crate::error::{Error, ErrorType};
+use crate::{if_no_std, if_std};
+use crate::io::{Buffer, ByteOrder, WriteRead};
+use crate::Result;
+use core::cmp::Ordering;
+use core::fmt::{Display, Formatter};
+use core::net::{Ipv4Addr, Ipv6Addr};
+use core::str::FromStr;
+
+#[cfg(feature = "bgp_multiprotocol")]
+use crate::bgp::opt_params::{AFI, SAFI};
+#[cfg(feature = "bgp_multiprotocol")]
+use crate::bgp::path_attr::link_state::LinkStateNLRI;
+#[cfg(feature = "bgp_multiprotocol")]
+use crate::bgp::{LabeledPrefix, PathPrefix};
+
+pub mod bgpsec;
+pub mod link_state;
+pub mod pmsi_tunnel;
+pub mod prefix_sid;
+pub mod tunnel_encap;
+
+if_no_std! {
+    use {
+        alloc::{
+            collections::BTreeSet,
+            format,
+            string::String,
+            vec::Vec,
+            vec
+        }
+    };
+}
+
+if_std! {
+    use std::collections::BTreeSet;
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Hash)]
+pub struct Attribute {
+    ty: AttributeType,
+    flags: AttributeFlags,
+    value: AttributeValue
+}
+
+impl WriteRead for Attribute {
+    fn write(&self, buffer: &mut Buffer) -> Result<()> {
+        self.write_with(buffer, &SessionParameters::default())
+    }
+
+    fn read(buffer: &mut Buffer) -> Result<Self> where Self: Sized {
+        Self::read_with(buffer, &SessionParameters::default())
+    }
+}
+
+impl Attribute {
+
+    pub fn new(ty: AttributeType, flags: AttributeFlags, value: AttributeValue) -> Self {
+        Self {
+            ty,
+            flags,
+            value
+        }
+    }
+
+    /// This function writes the attribute into the buffer. The encoding of some attributes like the
+    /// AS_PATH attribute depends on the parameters, that were negotiated with the peer.
+    pub fn write_with(&self, buffer: &mut Buffer, parameters: &SessionParameters) -> Result<()> {
+        if let Some(ty) = self.value.ty().filter(|ty| *ty != self.ty) {
+            return Err(ErrorType::WriteError.err(format!("Unable to write attribute {:?}! The value is a value of attribute {ty:?}", self.ty)));
+        }
+        self.validate_flags()?;
+
+        let temp_buffer = &mut Buffer::empty(ByteOrder::BigEndian);
+        match &self.value {
+            AttributeValue::Origin(origin) => (*origin as u8).write(temp_buffer)?,
+            AttributeValue::ASPath(path) => path.write_with(temp_buffer, parameters)?,
+            AttributeValue::AS4Path(path) => path.write(temp_buffer)?,
+            AttributeValue::NextHop(next_hop) => temp_buffer.write_bytes_vector(next_hop),
+            AttributeValue::MultiExitDisc(metric) => metric.write(temp_buffer)?,
+            AttributeValue::OnlyToCustomer(autonomous_system) => autonomous_system.write(temp_buffer)?,
+            AttributeValue::LocalPref(preference) => preference.write(temp_buffer)?,
+            AttributeValue::AtomicAggregate => {},
+            AttributeValue::Aggregator(autonomous_system, address) => {
+                if parameters.four_octet_as {
+                    autonomous_system.write(temp_buffer)?;
+                } else {
+                    two_octet_as(*autonomous_system)?.write(temp_buffer)?;
+                }
+                address.write(temp_buffer)?;
+            },
+            AttributeValue::AIGP(tlvs) => {
+                for tlv in tlvs {
+                    tlv.write(temp_buffer)?;
+                }
+            },
+            AttributeValue::PrefixSID(tlvs) => {
+                for tlv in tlvs {
+                    tlv.write(temp_buffer)?;
+                }
+            },
+            AttributeValue::PMSITunnel(tunnel) => tunnel.write(temp_buffer)?,
+            AttributeValue::BGPsecPath(path) => path.write(temp_buffer)?,
+            AttributeValue::AttributeSet(origin_as, attributes) => {
+                origin_as.write(temp_buffer)?;
+                for attribute in attributes {
+                    if !attribute.ty.is_allowed_in_attribute_set() {
+                        return Err(ErrorType::WriteError.err(format!("Unable to write attribute {:?} into ATTR_SET attribute!", attribute.ty)));
+                    }
+                    attribute.write_with(temp_buffer, parameters)?;
+                }
+            },
+            AttributeValue::LinkState(tlvs) => {
+                for tlv in tlvs {
+                    tlv.write(temp_buffer)?;
+                }
+            },
+            AttributeValue::TunnelEncapsulation(tlvs) => {
+                for tlv in tlvs {
+                    tlv.write(temp_buffer)?;
+                }
+            },
+            AttributeValue::AS4Aggregator(autonomous_system, address) => {
+                autonomous_system.write(temp_buffer)?;
+                address.write(temp_buffer)?;
+            },
+            AttributeValue::OriginatorId(originator_id) => originator_id.write(temp_buffer)?,
+            AttributeValue::ClusterList(cluster_list) => {
+                for cluster_id in cluster_list {
+                    cluster_id.write(temp_buffer)?;
+                }
+            },
+            AttributeValue::Communities(communities) => {
+                for community in communities {
+                    community.write(temp_buffer)?;
+                }
+            },
+            AttributeValue::LargeCommunities(communities) => {
+                for community in communities {
+                    community.write(temp_buffer)?;
+                }
+            },
+            AttributeValue::ExtendedCommunities(communities) => {
+                for community in communities {
+                    community.write(temp_buffer)?;
+                }
+            },
+            AttributeValue::Ipv6ExtendedCommunities(communities) => {
+                for community in communities {
+                    community.write(temp_buffer)?;
+                }
+            },
+            #[cfg(feature = "bgp_multiprotocol")]
+            AttributeValue::MPReachableNLRI(afi, safi, next_hop, nlri) => {
+                match (*afi).into() {
+                    Ok(value) => value.write(temp_buffer)?,
+                    Err(value) => return Err(
+                        ErrorType::ReadError.err(format!("Unexpected AFI value {value}!"))
+                    )
+                }
+
+                match (*safi).into() {
+                    Ok(value) => value.write(temp_buffer)?,
+                    Err(value) => return Err(
+                        ErrorType::ReadError.err(format!("Unexpected SAFI value {value}!"))
+                    )
+                }
+
+                (next_hop.len() as u8).write(temp_buffer)?;
+                temp_buffer.write_bytes_vector(next_hop);
+                (0_u8).write(temp_buffer)?;
+                nlri.write_with(temp_buffer, *afi, *safi, parameters, false)?;
+            },
+            #[cfg(feature = "bgp_multiprotocol")]
+            AttributeValue::MPUnreachableNLRI(afi, safi, withdrawn_routes) => {
+                match (*afi).into() {
+                    Ok(value) => value.write(temp_buffer)?,
+                    Err(value) => return Err(
+                        ErrorType::ReadError.err(format!("Unexpected AFI value {value}!"))
+                    )
+                }
+
+                match (*safi).into() {
+                    Ok(value) => value.write(temp_buffer)?,
+                    Err(value) => return Err(
+                        ErrorType::ReadError.err(format!("Unexpected SAFI value {value}!"))
+                    )
+                }
+
+                withdrawn_routes.write_with(temp_buffer, *afi, *safi, parameters, true)?;
+            },
+            AttributeValue::Unknown(data) => temp_buffer.write_bytes_vector(data)
+        }
+
+        // RFC4271, Section 4.3: The Extended Length bit is set, if the value exceeds 255 bytes
+        let extended_length = self.flags.contains(AttributeFlags::EXTENDED_LENGTH) || temp_buffer.len() > u8::MAX as usize;
+        if temp_buffer.len() > u16::MAX as usize {
+            return Err(ErrorType::WriteError.err(format!("Unable to write attribute {:?}! The value is {} bytes long but only 65535 bytes are allowed", self.ty, temp_buffer.len())));
+        }
+
+        if extended_length {
+            (self.flags | AttributeFlags::EXTENDED_LENGTH).bits().write(buffer)?;
+            u8::from(self.ty).write(buffer)?;
+            (temp_buffer.len() as u16).write(buffer)?;
+        } else {
+            self.flags.bits().write(buffer)?;
+            u8::from(self.ty).write(buffer)?;
+            (temp_buffer.len() as u8).write(buffer)?;
+        }
+        temp_buffer.write_buffer(buffer)
+    }
+
+    /// This function reads the attribute from the buffer. The encoding of some attributes like the
+    /// AS_PATH attribute depends on the parameters, that were negotiated with the peer.
+    pub fn read_with(buffer: &mut Buffer, parameters: &SessionParameters) -> Result<Self> {
+        let (flags, ty, length) = Self::read_header(buffer)?;
+        let temp_buffer = &mut Buffer::read_buffer(buffer, length)?;
+        Self::read_value(flags, ty, temp_buffer, parameters)
+    }
+
+    /// This function reads the flags, the type and the length of the value of the attribute from
+    /// the buffer.
+    pub(crate) fn read_header(buffer: &mut Buffer) -> Result<(AttributeFlags, AttributeType, usize)> {
+        let flags = AttributeFlags::from_bits_truncate(u8::read(buffer)?);
+        let ty = AttributeType::from(u8::read(buffer)?);
+
+        let length = if flags.contains(AttributeFlags::EXTENDED_LENGTH) {
+            u16::read(buffer)? as usize
+        } else {
+            u8::read(buffer)? as usize
+        };
+        Ok((flags, ty, length))
+    }
+
+    /// This function reads the value of the attribute from the buffer, which contains only the
+    /// value. If the value is shorter or longer than expected, a Attribute Length Error is returned.
+    /// Errors of the Update Message Error with another subcode are returned unchanged.
+    pub(crate) fn read_value(flags: AttributeFlags, ty: AttributeType, buffer: &mut Buffer, parameters: &SessionParameters) -> Result<Self> {
+        let length_error = |message: &str| ErrorType::BGPError(BGPError::update(UpdateMessageError::AttributeLengthError))
+            .err(format!("Unexpected length of attribute {ty:?}! {message}"));
+
+        let value = Self::decode_value(flags, ty, buffer, parameters).map_err(|error| match error.ty() {
+            ErrorType::BGPError(_) => error,
+            // Only the read beyond the end of the value is a error of the length
+            ErrorType::ReadError => length_error(error.message()),
+            // RFC4271, Section 6.3: Other errors of the value are reported with the subcode of the attribute
+            _ => {
+                let subcode = match ty {
+                    AttributeType::Origin => UpdateMessageError::InvalidOriginAttribute,
+                    AttributeType::ASPath | AttributeType::AS4Path => UpdateMessageError::MalformedASPath,
+                    AttributeType::NextHop => UpdateMessageError::InvalidNextHopAttribute,
+                    _ if flags.contains(AttributeFlags::OPTIONAL) => UpdateMessageError::OptionalAttributeError,
+                    _ => UpdateMessageError::MalformedAttributeList
+                };
+                ErrorType::BGPError(BGPError::update(subcode)).err(format!("Unexpected value of attribute {ty:?}! {}", error.message()))
+            }
+        })?;
+
+        if buffer.remaining() > 0 {
+            return Err(length_error(&format!("{} bytes remaining after read of the value!", buffer.remaining())));
+        }
+
+        let attribute = Self {
+            flags,
+            ty,
+            value
+        };
+        attribute.validate_flags()?;
+        Ok(attribute)
+    }
+
+    /// This function checks the Optional and Transitive bits of the attribute against the definition
+    /// of the attribute type and that the Partial bit is only set on optional transitive attributes,
+    /// as defined in [RFC4271, Section 6.3](https://www.rfc-editor.org/rfc/rfc4271#section-6.3).
+    /// Attributes without a known definition are only checked for the Partial bit.
+    pub fn validate_flags(&self) -> Result<()> {
+        let flags_error = || ErrorType::BGPError(BGPError::update(UpdateMessageError::AttributeFlagsError))
+            .err(format!("Unexpected flags {:?} of attribute {:?}!", self.flags, self.ty));
+
+        let category = self.flags & (AttributeFlags::OPTIONAL | AttributeFlags::TRANSITIVE);
+        if let Some(expected) = self.ty.expected_flags() {
+            if category != expected {
+                return Err(flags_error());
+            }
+        }
+
+        if self.flags.contains(AttributeFlags::PARTIAL) && category != (AttributeFlags::OPTIONAL | AttributeFlags::TRANSITIVE) {
+            return Err(flags_error());
+        }
+        Ok(())
+    }
+
+    fn decode_value(flags: AttributeFlags, ty: AttributeType, temp_buffer: &mut Buffer, parameters: &SessionParameters) -> Result<AttributeValue> {
+        Ok(match ty {
+            AttributeType::Origin => AttributeValue::Origin(Origin::from(u8::read(temp_buffer)?)?),
+            AttributeType::ASPath => AttributeValue::ASPath(ASPath::read_with(temp_buffer, parameters)?),
+            AttributeType::AS4Path => AttributeValue::AS4Path(ASPath::read(temp_buffer)?),
+            AttributeType::MultiExitDisc => AttributeValue::MultiExitDisc(u32::read(temp_buffer)?),
+            AttributeType::OnlyToCustomer => AttributeValue::OnlyToCustomer(u32::read(temp_buffer)?),
+            AttributeType::LocalPref => AttributeValue::LocalPref(u32::read(temp_buffer)?),
+            AttributeType::AtomicAggregate => AttributeValue::AtomicAggregate,
+            AttributeType::Aggregator => {
+                let autonomous_system = if parameters.four_octet_as {
+                    u32::read(temp_buffer)?
+                } else {
+                    u16::read(temp_buffer)? as u32
+                };
+                AttributeValue::Aggregator(autonomous_system, Ipv4Addr::read(temp_buffer)?)
+            },
+            AttributeType::AS4Aggregator => AttributeValue::AS4Aggregator(u32::read(temp_buffer)?, Ipv4Addr::read(temp_buffer)?),
+            AttributeType::AIGP => {
+                let mut tlvs = Vec::new();
+                while temp_buffer.remaining() > 0 {
+                    tlvs.push(AIGPTlv::read(temp_buffer)?);
+                }
+                AttributeValue::AIGP(tlvs)
+            },
+            AttributeType::BGPPrefixSID => {
+                let mut tlvs = Vec::new();
+                while temp_buffer.remaining() > 0 {
+                    tlvs.push(PrefixSIDTlv::read(temp_buffer)?);
+                }
+                AttributeValue::PrefixSID(tlvs)
+            },
+            AttributeType::PMSITunnel => AttributeValue::PMSITunnel(PMSITunnel::read(temp_buffer)?),
+            AttributeType::BGPSecPath => AttributeValue::BGPsecPath(BGPsecPath::read(temp_buffer)?),
+            AttributeType::AttributeSet => {
+                let origin_as = u32::read(temp_buffer)?;
+                let mut attributes = Vec::new();
+                while temp_buffer.remaining() > 0 {
+                    let attribute = Attribute::read_with(temp_buffer, parameters)?;
+                    if !attribute.ty.is_allowed_in_attribute_set() {
+                        return Err(ErrorType::BGPError(BGPError::update(UpdateMessageError::OptionalAttributeError))
+                            .err(format!("Unexpected attribute {:?} in ATTR_SET attribute!", attribute.ty)));
+                    }
+                    attributes.push(attribute);
+                }
+                AttributeValue::AttributeSet(origin_as, attributes)
+            },
+            AttributeType::BGPLSAttribute => {
+                let mut tlvs = Vec::new();
+                while temp_buffer.remaining() > 0 {
+                    tlvs.push(LinkStateAttributeTlv::read(temp_buffer)?);
+                }
+                AttributeValue::LinkState(tlvs)
+            },
+            AttributeType::TunnelEncapsulation => {
+                let mut tlvs = Vec::new();
+                while temp_buffer.remaining() > 0 {
+                    tlvs.push(TunnelTlv::read(temp_buffer)?);
+                }
+                AttributeValue::TunnelEncapsulation(tlvs)
+            },
+            AttributeType::NextHop => AttributeValue::NextHop(temp_buffer.read_bytes_vector(temp_buffer.len())?),
+            AttributeType::OriginatorId => AttributeValue::OriginatorId(Ipv4Addr::read(temp_buffer)?),
+            AttributeType::ClusterList => {
+                let mut cluster_list = Vec::new();
+                while temp_buffer.remaining() > 0 {
+                    cluster_list.push(u32::read(temp_buffer)?);
+                }
+                AttributeValue::ClusterList(cluster_list)
+            },
+            AttributeType::Community => {
+                let mut communities = Vec::new();
+                while temp_buffer.remaining() > 0 {
+                    communities.push(Community::read(temp_buffer)?);
+                }
+                AttributeValue::Communities(communities)
+            },
+            AttributeType::LargeCommunity => {
+                let mut communities = Vec::new();
+                while temp_buffer.remaining() > 0 {
+                    communities.push(LargeCommunity::read(temp_buffer)?);
+                }
+                AttributeValue::LargeCommunities(communities)
+            },
+            AttributeType::ExtendedCommunities => {
+                let mut communities = Vec::new();
+                while temp_buffer.remaining() > 0 {
+                    communities.push(ExtendedCommunity::read(temp_buffer)?);
+                }
+                AttributeValue::ExtendedCommunities(communities)
+            },
+            AttributeType::Ipv6AddressSpecifiedExtendedCommunity => {
+                let mut communities = Vec::new();
+                while temp_buffer.remaining() > 0 {
+                    communities.push(Ipv6ExtendedCommunity::read(temp_buffer)?);
+                }
+                AttributeValue::Ipv6ExtendedCommunities(communities)
+            },
+            #[cfg(feature = "bgp_multiprotocol")]
+            AttributeType::MPReachableNLRI => {
+                let afi = AFI::from(u16::read(temp_buffer)?);
+                let safi = SAFI::from(u8::read(temp_buffer)?);
+                let next_hop_length = u8::read(temp_buffer)?;
+                let next_hop = temp_buffer.read_bytes_vector(next_hop_length as usize)?;
+                u8::read(temp_buffer)?;
+                let nlri = MPNLRI::read_with(temp_buffer, afi, safi, parameters, false)?;
+
+                AttributeValue::MPReachableNLRI(
+                    afi,
+                    safi,
+                    next_hop,
+                    nlri
+                )
+            },
+            #[cfg(feature = "bgp_multiprotocol")]
+            AttributeType::MPUnreachableNLRI => {
+                let afi = AFI::from(u16::read(temp_buffer)?);
+                let safi = SAFI::from(u8::read(temp_buffer)?);
+                AttributeValue::MPUnreachableNLRI(afi, safi, MPNLRI::read_with(temp_buffer, afi, safi, parameters, true)?)
+            }
+            // RFC4271, Section 5: Unrecognized well-known attributes are an error, but unrecognized
+            // optional attributes are preserved for the pass-through
+            _ if !flags.contains(AttributeFlags::OPTIONAL) => {
+                return Err(ErrorType::BGPError(BGPError::update(UpdateMessageError::UnrecognizedWellKnownAttribute))
+                    .err(format!("Unrecognized well-known attribute with type {}!", u8::from(ty))));
+            },
+            _ => AttributeValue::Unknown(temp_buffer.read_bytes_vector(temp_buffer.remaining())?)
+        })
+    }
+
+    pub fn ty(&self) -> AttributeType {
+        self.ty
+    }
+
+    pub fn flags(&self) -> AttributeFlags {
+        self.flags
+    }
+
+    pub fn value(&self) -> &AttributeValue {
+        &self.value
+    }
+
+    pub fn value_mut(&mut self) -> &mut AttributeValue {
+        &mut self.value
+    }
+
+}
+
+/// This function prepares the unrecognized optional attributes of a route for the advertisement to
+/// other peers, as described in [RFC4271, Section 5](https://www.rfc-editor.org/rfc/rfc4271#section-5).
+/// Unrecognized optional transitive attributes are passed with the Partial bit set and
+/// unrecognized optional non-transitive attributes are removed from the list of attributes.
+pub fn propagate_unknown_attributes(attributes: &mut Vec<Attribute>) {
+    attributes.retain_mut(|attribute| {
+        if !matches!(attribute.value, AttributeValue::Unknown(_)) {
+            return true;
+        }
+
+        if !attribute.flags.contains(AttributeFlags::TRANSITIVE) {
+            return false;
+        }
+
+        attribute.flags |= AttributeFlags::PARTIAL;
+        true
+    });
+}
+
+/// This function reads all attributes of a Update packet with the revised error handling, as
+/// defined in [RFC7606](https://www.rfc-editor.org/rfc/rfc7606). Malformed attributes are not
+/// returned but a diagnostic with the approach for the error is added to the list of diagnostics.
+/// Errors, that require a session reset, are returned by this function.
+pub(crate) fn read_attributes_revised(buffer: &mut Buffer, parameters: &SessionParameters, diagnostics: &mut Vec<UpdateDiagnostic>) -> Result<Vec<Attribute>> {
+    let mut attributes: Vec<Attribute> = Vec::new();
+    let mut seen_types = BTreeSet::new();
+    while buffer.remaining() > 0 {
+        // RFC7606, Section 4: If the attributes can't be parsed, the attribute is handled with
+        // Treat-as-withdraw. The NLRI is still located by the Total Path Attribute Length.
+        let (flags, ty, length) = match Attribute::read_header(buffer) {
+            Ok(header) => header,
+            Err(error) => {
+                diagnostics.push(UpdateDiagnostic {
+                    type_code: u8::from(AttributeType::Reserved),
+                    action: ErrorAction::TreatAsWithdraw,
+                    error: ErrorType::BGPError(BGPError::update(UpdateMessageError::MalformedAttributeList)).err(error.message())
+                });
+                break;
+            }
+        };
+
+        if length > buffer.remaining() {
+            diagnostics.push(UpdateDiagnostic {
+                type_code: u8::from(ty),
+                action: ErrorAction::TreatAsWithdraw,
+                error: ErrorType::BGPError(BGPError::update(UpdateMessageError::AttributeLengthError))
+                    .err(format!("Unexpected length of attribute {ty:?}! Attribute is {length} bytes long, but only {} bytes are remaining", buffer.remaining()))
+            });
+            break;
+        }
+        let value_buffer = &mut Buffer::read_buffer(buffer, length)?;
+
+        // RFC7606, Section 3 (g): Only the first occurrence of an attribute is used, but multiple
+        // MP_REACH_NLRI or MP_UNREACH_NLRI attributes are handled with a session reset. The type is
+        // marked before the decoding, so the repeat of a malformed attribute is a duplicate too.
+        if !seen_types.insert(u8::from(ty)) {
+            let error = ErrorType::BGPError(BGPError::update(UpdateMessageError::MalformedAttributeList))
+                .err(format!("Unexpected duplicate of attribute {ty:?}!"));
+            if ty.error_action() == ErrorAction::SessionReset {
+                return Err(error);
+            }
+
+            diagnostics.push(UpdateDiagnostic {
+                type_code: u8::from(ty),
+                action: ErrorAction::AttributeDiscard,
+                error
+            });
+            continue;
+        }
+
+        match Attribute::read_value(flags, ty, value_buffer, parameters) {
+            Ok(attribute) => attributes.push(attribute),
+            Err(error) => {
+                let unrecognized = error.ty() == ErrorType::BGPError(BGPError::update(UpdateMessageError::UnrecognizedWellKnownAttribute));
+                if unrecognized || ty.error_action() == ErrorAction::SessionReset {
+                    return Err(error);
+                }
+
+                diagnostics.push(UpdateDiagnostic {
+                    type_code: u8::from(ty),
+                    action: ty.error_action(),
+                    error
+                });
+            }
+        }
+    }
+    Ok(attributes)
+}
+
+/// This function returns the well-known attributes, that are mandatory in a Update packet. ORIGIN,
+/// AS_PATH and NEXT_HOP are required for NLRI in the packet, as defined in
+/// [RFC4271, Section 5](https://www.rfc-editor.org/rfc/rfc4271#section-5). Packets with only a
+/// MP_REACH_NLRI attribute require ORIGIN and AS_PATH, as defined in
+/// [RFC4760, Section 3](https://www.rfc-editor.org/rfc/rfc4760#section-3).
+fn mandatory_attributes(attributes: &[Attribute], has_nlri: bool) -> &'static [AttributeType] {
+    if has_nlri {
+        return &[AttributeType::Origin, AttributeType::ASPath, AttributeType::NextHop];
+    }
+
+    #[cfg(feature = "bgp_multiprotocol")]
+    if attributes.iter().any(|attribute| attribute.ty == AttributeType::MPReachableNLRI) {
+        return &[AttributeType::Origin, AttributeType::ASPath];
+    }
+
+    let _ = attributes;
+    &[]
+}
+
+/// This function returns the first mandatory well-known attribute, that is missing in the
+/// attributes of a Update packet.
+pub(crate) fn missing_attribute(attributes: &[Attribute], has_nlri: bool) -> Option<AttributeType> {
+    mandatory_attributes(attributes, has_nlri).iter()
+        .find(|ty| !attributes.iter().any(|attribute| attribute.ty == **ty))
+        .copied()
+}
+
+/// This function validates the attribute list of a Update packet. Duplicate attributes are reported
+/// as Malformed Attribute List and missing mandatory well-known attributes are reported as Missing
+/// Well-known Attribute, as defined in [RFC4271, Section 6.3](https://www.rfc-editor.org/rfc/rfc4271#section-6.3).
+pub fn validate_attributes(attributes: &[Attribute], has_nlri: bool) -> Result<()> {
+    for (index, attribute) in attributes.iter().enumerate() {
+        if attributes[..index].iter().any(|other| other.ty == attribute.ty) {
+            return Err(ErrorType::BGPError(BGPError::update(UpdateMessageError::MalformedAttributeList))
+                .err(format!("Unexpected duplicate of attribute {:?}!", attribute.ty)));
+        }
+    }
+
+    if let Some(ty) = missing_attribute(attributes, has_nlri) {
+        return Err(ErrorType::BGPError(BGPError::update(UpdateMessageError::MissingWellKnownAttribute))
+            .err(format!("Missing well-known attribute {ty:?}!")));
+    }
+    Ok(())
+}
+
+/// This function removes all attributes, that are not required to withdraw the routes of a Update
+/// packet, which is handled with Treat-as-withdraw. The routes of the MP_REACH_NLRI attributes are
+/// moved into MP_UNREACH_NLRI attributes.
+pub(crate) fn treat_as_withdraw(attributes: Vec<Attribute>) -> Vec<Attribute> {
+    attributes.into_iter().filter_map(|attribute| match attribute.value {
+        #[cfg(feature = "bgp_multiprotocol")]
+        AttributeValue::MPReachableNLRI(afi, safi, _, nlri) => Some(Attribute::new(
+            AttributeType::MPUnreachableNLRI,
+            AttributeFlags::OPTIONAL,
+            AttributeValue::MPUnreachableNLRI(afi, safi, nlri)
+        )),
+        #[cfg(feature = "bgp_multiprotocol")]
+        AttributeValue::MPUnreachableNLRI(_, _, _) => Some(attribute),
+        _ => None
+    }).collect()
+}
+
+/// This is the reserved 2-byte AS number AS_TRANS, which is used by routers with support of
+/// four-octet AS numbers as replacement for non-mappable AS numbers, if the peer doesn't support
+/// four-octet AS numbers. This AS number is defined in
+/// [RFC6793, Section 9](https://www.rfc-editor.org/rfc/rfc6793#section-9).
+pub const AS_TRANS: u32 = 23456;
+
+fn two_octet_as(autonomous_system: u32) -> Result<u16> {
+    u16::try_from(autonomous_system).map_err(|_| ErrorType::WriteError.err(format!(
+        "Unable to write AS number {autonomous_system} as 2-byte AS number! Replace it with AS_TRANS before sending it to the peer."
+    )))
+}
+
+/// This function reconstructs the AS_PATH and AGGREGATOR attributes of a route, which was received
+/// from a peer without support of four-octet AS numbers, as described in
+/// [RFC6793, Section 4.2.3](https://www.rfc-editor.org/rfc/rfc6793#section-4.2.3). The
+/// AS4_PATH and AS4_AGGREGATOR attributes are removed from the list of attributes.
+///
+/// The AS4_PATH and AS4_AGGREGATOR attributes are ignored, if the AGGREGATOR attribute contains
+/// another AS number than AS_TRANS. The AS4_PATH attribute is also ignored, if the AS_PATH attribute
+/// is shorter than the AS4_PATH attribute. Otherwise the leading AS numbers of the AS_PATH, which
+/// are missing in the AS4_PATH, are prepended to the AS4_PATH.
+pub fn reconstruct_as4_attributes(attributes: &mut Vec<Attribute>) {
+    let mut as4_path = None;
+    let mut as4_aggregator = None;
+    attributes.retain(|attribute| match attribute.value() {
+        AttributeValue::AS4Path(path) => {
+            as4_path = Some(path.clone());
+            false
+        },
+        AttributeValue::AS4Aggregator(autonomous_system, address) => {
+            as4_aggregator = Some((*autonomous_system, *address));
+            false
+        },
+        _ => true
+    });
+
+    for attribute in attributes.iter_mut() {
+        if let AttributeValue::Aggregator(autonomous_system, address) = attribute.value_mut() {
+            if *autonomous_system != AS_TRANS {
+                return;
+            }
+
+            if let Some((as4_autonomous_system, as4_address)) = as4_aggregator {
+                *autonomous_system = as4_autonomous_system;
+                *address = as4_address;
+            }
+        }
+    }
+
+    let Some(mut as4_path) = as4_path else {
+        return;
+    };
+    as4_path.remove_confederation_segments();
+
+    for attribute in attributes.iter_mut() {
+        if let AttributeValue::ASPath(path) = attribute.value_mut() {
+            if path.path_length() < as4_path.path_length() {
+                return;
+            }
+
+            let mut remaining = path.path_length() - as4_path.path_length();
+            let mut segments = Vec::new();
+            for segment in path.segments() {
+                match segment {
+                    ASPathSegment::ASConfedSequence(_) | ASPathSegment::ASConfedSet(_) => segments.push(segment.clone()),
+                    _ if remaining == 0 => break,
+                    ASPathSegment::ASSequence(values) => {
+                        let count = remaining.min(values.len());
+                        segments.push(ASPathSegment::ASSequence(values[..count].to_vec()));
+                        remaining -= count;
+                    },
+                    ASPathSegment::ASSet(_) => {
+                        segments.push(segment.clone());
+                        remaining -= 1;
+                    }
+                }
+            }
+
+            segments.extend_from_slice(as4_path.segments());
+            *path = ASPath::new(segments);
+        }
+    }
+}
+
+/// This function translates the AS_PATH and AGGREGATOR attributes of a route for a peer without
+/// support of four-octet AS numbers, as described in
+/// [RFC6793, Section 4.2.2](https://www.rfc-editor.org/rfc/rfc6793#section-4.2.2). All
+/// non-mappable AS numbers are replaced with AS_TRANS and the original attributes are sent as
+/// AS4_PATH and AS4_AGGREGATOR attributes. The AS4_PATH attribute doesn't contain the
+/// confederation segments.
+pub fn translate_as4_attributes(attributes: &mut Vec<Attribute>) {
+    attributes.retain(|attribute| !matches!(attribute.value(), AttributeValue::AS4Path(_) | AttributeValue::AS4Aggregator(_, _)));
+
+    let is_non_mappable = |value: &u32| *value > u16::MAX as u32;
+    let mut as4_attributes = Vec::new();
+    for attribute in attributes.iter_mut() {
+        match attribute.value_mut() {
+            AttributeValue::ASPath(path) if path.segments().iter().any(|segment| segment.values().iter().any(is_non_mappable)) => {
+                let mut as4_path = path.clone();
+                as4_path.remove_confederation_segments();
+                as4_attributes.push(Attribute::new(
+                    AttributeType::AS4Path,
+                    AttributeFlags::OPTIONAL | AttributeFlags::TRANSITIVE,
+                    AttributeValue::AS4Path(as4_path)
+                ));
+
+                let segments = path.segments().iter().cloned().map(|mut segment| {
+                    segment
+                        .values_mut()
+                        .iter_mut()
+                        .filter(|value| is_non_mappable(value))
+                        .for_each(|value| *value = AS_TRANS);
+                    segment
+                }).collect();
+                *path = ASPath::new(segments);
+            },
+            AttributeValue::Aggregator(autonomous_system, address) if is_non_mappable(autonomous_system) => {
+                as4_attributes.push(Attribute::new(
+                    AttributeType::AS4Aggregator,
+                    AttributeFlags::OPTIONAL | AttributeFlags::TRANSITIVE,
+                    AttributeValue::AS4Aggregator(*autonomous_system, *address)
+                ));
+                *autonomous_system = AS_TRANS;
+            },
+            _ => {}
+        }
+    }
+    attributes.append(&mut as4_attributes);
+}
+
+/// This function prepends the cluster id of the local route reflector to the CLUSTER_LIST
+/// attribute, as described in [RFC4456, Section 8](https://www.rfc-editor.org/rfc/rfc4456#section-8).
+/// If the list of attributes contains no CLUSTER_LIST attribute, a new attribute with the cluster
+/// id as single element is created.
+pub fn prepend_cluster_id(attributes: &mut Vec<Attribute>, cluster_id: u32) {
+    for attribute in attributes.iter_mut() {
+        if let AttributeValue::ClusterList(cluster_list) = attribute.value_mut() {
+            cluster_list.insert(0, cluster_id);
+            return;
+        }
+    }
+
+    attributes.push(Attribute::new(
+        AttributeType::ClusterList,
+        AttributeFlags::OPTIONAL,
+        AttributeValue::ClusterList(vec![cluster_id])
+    ));
+}
+
+/// This function inserts the ORIGINATOR_ID attribute with the BGP identifier of the originator of
+/// the route into the list of attributes. As described in
+/// [RFC4456, Section 8](https://www.rfc-editor.org/rfc/rfc4456#section-8), a route reflector
+/// doesn't override an existing ORIGINATOR_ID attribute.
+pub fn insert_originator_id(attributes: &mut Vec<Attribute>, originator_id: Ipv4Addr) {
+    if attributes.iter().any(|attribute| matches!(attribute.value(), AttributeValue::OriginatorId(_))) {
+        return;
+    }
+
+    attributes.push(Attribute::new(
+        AttributeType::OriginatorId,
+        AttributeFlags::OPTIONAL,
+        AttributeValue::OriginatorId(originator_id)
+    ));
+}
+
+/// This function checks the attributes of a reflected route for a reflection loop. A loop is
+/// detected, if the ORIGINATOR_ID attribute is the BGP identifier of the local router or the
+/// CLUSTER_LIST attribute contains the cluster id of the local router. These routes should be
+/// ignored by the router, as described in
+/// [RFC4456, Section 8](https://www.rfc-editor.org/rfc/rfc4456#section-8).
+pub fn is_reflection_loop(attributes: &[Attribute], router_id: Ipv4Addr, cluster_id: u32) -> bool {
+    attributes.iter().any(|attribute| match attribute.value() {
+        AttributeValue::OriginatorId(originator_id) => *originator_id == router_id,
+        AttributeValue::ClusterList(cluster_list) => cluster_list.contains(&cluster_id),
+        _ => false
+    })
+}
+
+/// This function returns the accumulated IGP metric of the first AIGP TLV of the AIGP attribute, as
+/// defined in [RFC7311, Section 3](https://www.rfc-editor.org/rfc/rfc7311#section-3). Further AIGP
+/// TLVs are ignored.
+pub fn accumulated_igp_metric(attributes: &[Attribute]) -> Option<u64> {
+    attributes.iter().find_map(|attribute| match attribute.value() {
+        AttributeValue::AIGP(tlvs) => tlvs.iter().find_map(|tlv| match tlv {
+            AIGPTlv::AccumulatedMetric(metric) => Some(*metric),
+            _ => None
+        }),
+        _ => None
+    })
+}
+
+/// This function increments the accumulated IGP metric of the AIGP attribute by the IGP distance
+/// to the next hop, which is required before re-advertising the route with a changed next hop, as
+/// described in [RFC7311, Section 3.4](https://www.rfc-editor.org/rfc/rfc7311#section-3.4). The
+/// metric saturates at the maximum value. Routes without AIGP attribute aren't changed.
+pub fn increment_accumulated_igp_metric(attributes: &mut [Attribute], distance: u64) {
+    for attribute in attributes.iter_mut() {
+        if let AttributeValue::AIGP(tlvs) = attribute.value_mut() {
+            if let Some(AIGPTlv::AccumulatedMetric(metric)) = tlvs.iter_mut().find(|tlv| matches!(tlv, AIGPTlv::AccumulatedMetric(_))) {
+                *metric = metric.saturating_add(distance);
+            }
+            return;
+        }
+    }
+}
+
+/// This function compares the AIGP metrics of two routes for the decision process, as described in
+/// [RFC7311, Section 4](https://www.rfc-editor.org/rfc/rfc7311#section-4). The metric of a route is
+/// the accumulated IGP metric plus the IGP distance to the next hop of the route. A route with
+/// AIGP attribute is preferred over a route without AIGP attribute. The function returns
+/// [Ordering::Less], if the first route is preferred.
+pub fn compare_accumulated_igp_metric(first: &[Attribute], first_distance: u64, second: &[Attribute], second_distance: u64) -> Ordering {
+    let first = accumulated_igp_metric(first).map(|metric| metric.saturating_add(first_distance));
+    let second = accumulated_igp_metric(second).map(|metric| metric.saturating_add(second_distance));
+    match (first, second) {
+        (Some(first), Some(second)) => first.cmp(&second),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal
+    }
+}
+
+/// This function applies the ingress procedure of the Only to Customer (OTC) attribute to a route
+/// received from the peer, as described in
+/// [RFC9234, Section 5](https://www.rfc-editor.org/rfc/rfc9234#section-5). The role is the local
+/// role of the session. The function returns false, if the route is a route leak and must be
+/// considered ineligible. Otherwise the OTC attribute with the AS of the peer is added to routes
+/// received from a provider, a peer or a route server.
+pub fn only_to_customer_ingress(attributes: &mut Vec<Attribute>, role: Role, remote_as: u32) -> bool {
+    let otc = only_to_customer(attributes);
+    match (role, otc) {
+        (Role::Provider | Role::RouteServer, Some(_)) => return false,
+        (Role::Peer, Some(autonomous_system)) if autonomous_system != remote_as => return false,
+        (Role::Customer | Role::RouteServerClient | Role::Peer, None) => attributes.push(Attribute::new(
+            AttributeType::OnlyToCustomer,
+            AttributeFlags::OPTIONAL | AttributeFlags::TRANSITIVE,
+            AttributeValue::OnlyToCustomer(remote_as)
+        )),
+        _ => {}
+    }
+    true
+}
+
+/// This function applies the egress procedure of the Only to Customer (OTC) attribute to a route,
+/// which is advertised to the peer, as described in
+/// [RFC9234, Section 5](https://www.rfc-editor.org/rfc/rfc9234#section-5). The role is the local
+/// role of the session. The function returns false, if the route contains the OTC attribute and
+/// must not be advertised to a provider, a peer or a route server. Otherwise the OTC attribute with
+/// the local AS is added to routes advertised to a customer, a peer or a route server client.
+pub fn only_to_customer_egress(attributes: &mut Vec<Attribute>, role: Role, local_as: u32) -> bool {
+    let otc = only_to_customer(attributes);
+    match (role, otc) {
+        (Role::Customer | Role::RouteServerClient | Role::Peer, Some(_)) => return false,
+        (Role::Provider | Role::RouteServer | Role::Peer, None) => attributes.push(Attribute::new(
+            AttributeType::OnlyToCustomer,
+            AttributeFlags::OPTIONAL | AttributeFlags::TRANSITIVE,
+            AttributeValue::OnlyToCustomer(local_as)
+        )),
+        _ => {}
+    }
+    true
+}
+
+fn only_to_customer(attributes: &[Attribute]) -> Option<u32> {
+    attributes.iter().find_map(|attribute| match attribute.value() {
+        AttributeValue::OnlyToCustomer(autonomous_system) => Some(*autonomous_system),
+        _ => None
+    })
+}
+
+/// This function pushes the attributes of a route received from a customer into a ATTR_SET
+/// attribute with the AS of the customer network, as described in
+/// [RFC6368, Section 5](https://www.rfc-editor.org/rfc/rfc6368#section-5). The attributes, which
+/// aren't allowed in the ATTR_SET attribute, are kept. The ORIGIN attribute is copied and a empty
+/// AS_PATH attribute is added, so the provider can add its own attributes to the route.
+pub fn push_attribute_set(attributes: &mut Vec<Attribute>, origin_as: u32) {
+    let (kept, pushed): (Vec<Attribute>, Vec<Attribute>) = attributes.drain(..)
+        .partition(|attribute| !attribute.ty.is_allowed_in_attribute_set());
+    *attributes = kept;
+
+    if let Some(origin) = pushed.iter().find(|attribute| attribute.ty == AttributeType::Origin) {
+        attributes.push(origin.clone());
+    }
+    attributes.push(Attribute::new(
+        AttributeType::ASPath,
+        AttributeFlags::TRANSITIVE,
+        AttributeValue::ASPath(ASPath::new(Vec::new()))
+    ));
+    attributes.push(Attribute::new(
+        AttributeType::AttributeSet,
+        AttributeFlags::OPTIONAL | AttributeFlags::TRANSITIVE,
+        AttributeValue::AttributeSet(origin_as, pushed)
+    ));
+}
+
+/// This function replaces the attributes of the provider with the attributes of the ATTR_SET
+/// attribute before the route is advertised to the customer, as described in
+/// [RFC6368, Section 5](https://www.rfc-editor.org/rfc/rfc6368#section-5). The attributes, which
+/// aren't allowed in the ATTR_SET attribute, are kept. If the origin AS of the ATTR_SET attribute
+/// differs from the AS of the customer network, the origin AS is prepended to the AS_PATH
+/// attribute. The function returns false, if the route contains no ATTR_SET attribute.
+pub fn pop_attribute_set(attributes: &mut Vec<Attribute>, local_as: u32) -> bool {
+    let Some(position) = attributes.iter().position(|attribute| matches!(attribute.value(), AttributeValue::AttributeSet(..))) else {
+        return false;
+    };
+    let AttributeValue::AttributeSet(origin_as, mut popped) = attributes.remove(position).value else {
+        return false;
+    };
+
+    if origin_as != local_as {
+        for attribute in popped.iter_mut() {
+            if let AttributeValue::ASPath(path) = attribute.value_mut() {
+                path.prepend(origin_as);
+            }
+        }
+    }
+    attributes.retain(|attribute| !attribute.ty.is_allowed_in_attribute_set());
+    attributes.append(&mut popped);
+    true
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Hash)]
+pub enum AttributeValue {
+    Origin(Origin),
+    ASPath(ASPath),
+    NextHop(Vec<u8>),
+    MultiExitDisc(u32),
+    LocalPref(u32),
+    AtomicAggregate,
+    Aggregator(u32, Ipv4Addr),
+    OriginatorId(Ipv4Addr),
+    ClusterList(Vec<u32>),
+    Communities(Vec<Community>),
+    LargeCommunities(Vec<LargeCommunity>),
+    ExtendedCommunities(Vec<ExtendedCommunity>),
+    Ipv6ExtendedCommunities(Vec<Ipv6ExtendedCommunity>),
+    AS4Path(ASPath),
+    AS4Aggregator(u32, Ipv4Addr),
+    AIGP(Vec<AIGPTlv>),
+    PrefixSID(Vec<PrefixSIDTlv>),
+    TunnelEncapsulation(Vec<TunnelTlv>),
+    PMSITunnel(PMSITunnel),
+    LinkState(Vec<LinkStateAttributeTlv>),
+    BGPsecPath(BGPsecPath),
+    /// This attribute contains the AS number, which marked the route as propagated only to
+    /// customers, as defined in [RFC9234, Section 5](https://www.rfc-editor.org/rfc/rfc9234#section-5).
+    OnlyToCustomer(u32),
+    /// This attribute contains the origin AS and the attributes of a customer network, which are
+    /// carried transparently through the network of a VPN provider, as defined in
+    /// [RFC6368, Section 5](https://www.rfc-editor.org/rfc/rfc6368#section-5).
+    AttributeSet(u32, Vec<Attribute>),
+    #[cfg(feature = "bgp_multiprotocol")]
+    MPReachableNLRI(AFI, SAFI, Vec<u8>, MPNLRI),
+    #[cfg(feature = "bgp_multiprotocol")]
+    MPUnreachableNLRI(AFI, SAFI, MPNLRI),
+
+    /// This is the representation of a optional attribute, which isn't recognized by this library.
+    /// The value of the attribute is preserved as raw bytes, so the attribute can be passed to other
+    /// peers with the type code and the flags of the attribute.
+    Unknown(Vec<u8>)
+}
+
+impl AttributeValue {
+
+    /// This function returns the type of the attribute, that is represented by this value. Unknown
+    /// values have no type, because the type is only stored in the attribute.
+    pub fn ty(&self) -> Option<AttributeType> {
+        Some(match self {
+            Self::Origin(_) => AttributeType::Origin,
+            Self::ASPath(_) => AttributeType::ASPath,
+            Self::NextHop(_) => AttributeType::NextHop,
+            Self::MultiExitDisc(_) => AttributeType::MultiExitDisc,
+            Self::LocalPref(_) => AttributeType::LocalPref,
+            Self::AtomicAggregate => AttributeType::AtomicAggregate,
+            Self::Aggregator(..) => AttributeType::Aggregator,
+            Self::OriginatorId(_) => AttributeType::OriginatorId,
+            Self::ClusterList(_) => AttributeType::ClusterList,
+            Self::Communities(_) => AttributeType::Community,
+            Self::LargeCommunities(_) => AttributeType::LargeCommunity,
+            Self::ExtendedCommunities(_) => AttributeType::ExtendedCommunities,
+            Self::Ipv6ExtendedCommunities(_) => AttributeType::Ipv6AddressSpecifiedExtendedCommunity,
+            Self::AS4Path(_) => AttributeType::AS4Path,
+            Self::AS4Aggregator(..) => AttributeType::AS4Aggregator,
+            Self::AIGP(_) => AttributeType::AIGP,
+            Self::PrefixSID(_) => AttributeType::BGPPrefixSID,
+            Self::TunnelEncapsulation(_) => AttributeType::TunnelEncapsulation,
+            Self::PMSITunnel(_) => AttributeType::PMSITunnel,
+            Self::LinkState(_) => AttributeType::BGPLSAttribute,
+            Self::BGPsecPath(_) => AttributeType::BGPSecPath,
+            Self::OnlyToCustomer(_) => AttributeType::OnlyToCustomer,
+            Self::AttributeSet(..) => AttributeType::AttributeSet,
+            #[cfg(feature = "bgp_multiprotocol")]
+            Self::MPReachableNLRI(..) => AttributeType::MPReachableNLRI,
+            #[cfg(feature = "bgp_multiprotocol")]
+            Self::MPUnreachableNLRI(..) => AttributeType::MPUnreachableNLRI,
+            Self::Unknown(_) => return None
+        })
+    }
+
+}
+
+/// This is the representation of a TLV of the AIGP attribute, as defined in
+/// [RFC7311, Section 3](https://www.rfc-editor.org/rfc/rfc7311#section-3). The length of the TLV
+/// on the wire contains the type and the length field.
+#[derive(Clone, PartialEq, Eq, Debug, Hash)]
+pub enum AIGPTlv {
+    /// This TLV contains the accumulated IGP metric of the route (Type 1).
+    AccumulatedMetric(u64),
+    Unknown(u8, Vec<u8>)
+}
+
+impl WriteRead for AIGPTlv {
+    fn write(&self, buffer: &mut Buffer) -> Result<()> {
+        match self {
+            Self::AccumulatedMetric(metric) => {
+                1u8.write(buffer)?;
+                11u16.write(buffer)?;
+                metric.write(buffer)
+            },
+            Self::Unknown(ty, value) => {
+                if value.len() > u16::MAX as usize - 3 {
+                    return Err(ErrorType::WriteError.err(format!("Unable to write AIGP TLV {ty}! The value is {} bytes long", value.len())));
+                }
+                ty.write(buffer)?;
+                (value.len() as u16 + 3).write(buffer)?;
+                buffer.write_bytes_vector(value);
+                Ok(())
+            }
+        }
+    }
+
+    fn read(buffer: &mut Buffer) -> Result<Self> where Self: Sized {
+        let ty = u8::read(buffer)?;
+        let length = u16::read(buffer)? as usize;
+        let malformed = || ErrorType::BGPError(BGPError::update(UpdateMessageError::OptionalAttributeError))
+            .err(format!("Unexpected length {length} of AIGP TLV {ty}!"));
+        match ty {
+            1 if length == 11 => Ok(Self::AccumulatedMetric(u64::read(buffer)?)),
+            1 => Err(malformed()),
+            _ if length < 3 => Err(malformed()),
+            _ => Ok(Self::Unknown(ty, buffer.read_bytes_vector(length - 3)?))
+        }
+    }
+}
+
+/// This is the representation of the NLRI of the MP_REACH_NLRI and MP_UNREACH_NLRI attributes. The
+/// NLRI is decoded depending on the AFI and SAFI of the attribute. The NLRI of other address
+/// families is preserved as raw bytes.
+#[cfg(feature = "bgp_multiprotocol")]
+#[derive(Clone, PartialEq, Eq, Debug, Hash)]
+pub enum MPNLRI {
+    /// This is the NLRI of the IPv4 and IPv6 address families with the unicast and multicast SAFI.
+    Prefixes(Vec<PathPrefix>),
+    /// This is the NLRI of the IPv4 and IPv6 address families with the labeled unicast SAFI, as
+    /// defined in [RFC8277](https://www.rfc-editor.org/rfc/rfc8277).
+    LabeledPrefixes(Vec<LabeledPrefix>),
+    /// This is the NLRI of the BGP-LS address family (AFI 16388, SAFI 71).
+    LinkState(Vec<LinkStateNLRI>),
+    Unknown(Vec<u8>)
+}
+
+#[cfg(feature = "bgp_multiprotocol")]
+impl MPNLRI {
+
+    /// This function writes the NLRI into the buffer. The withdrawn routes of the MP_UNREACH_NLRI
+    /// attribute are encoded differently for some address families.
+    fn write_with(&self, buffer: &mut Buffer, afi: AFI, safi: SAFI, parameters: &SessionParameters, withdrawn: bool) -> Result<()> {
+        match self {
+            Self::Prefixes(prefixes) => {
+                for prefix in prefixes {
+                    prefix.write_with(buffer, afi, safi, parameters)?;
+                }
+            },
+            Self::LabeledPrefixes(prefixes) => {
+                for prefix in prefixes {
+                    match withdrawn {
+                        true => prefix.write_withdrawal(buffer, afi, safi, parameters)?,
+                        false => prefix.write_with(buffer, afi, safi, parameters)?
+                    }
+                }
+            },
+            Self::LinkState(nlri) => {
+                for nlri in nlri {
+                    nlri.write(buffer)?;
+                }
+            },
+            Self::Unknown(data) => buffer.write_bytes_vector(data)
+        }
+        Ok(())
+    }
+
+    /// This function reads the NLRI of the address family from the remaining bytes of the buffer.
+    fn read_with(buffer: &mut Buffer, afi: AFI, safi: SAFI, parameters: &SessionParameters, withdrawn: bool) -> Result<Self> {
+        Ok(match (afi, safi) {
+            (AFI::IPv4 | AFI::IPv6, SAFI::Unicast | SAFI::Multicast) => {
+                let mut prefixes = Vec::new();
+                while buffer.remaining() > 0 {
+                    prefixes.push(PathPrefix::read_with(buffer, afi, safi, parameters)?);
+                }
+                Self::Prefixes(prefixes)
+            },
+            (AFI::IPv4 | AFI::IPv6, SAFI::LabeledUnicast) => {
+                let mut prefixes = Vec::new();
+                while buffer.remaining() > 0 {
+                    prefixes.push(match withdrawn {
+                        true => LabeledPrefix::read_withdrawal(buffer, afi, safi, parameters)?,
+                        false => LabeledPrefix::read_with(buffer, afi, safi, parameters)?
+                    });
+                }
+                Self::LabeledPrefixes(prefixes)
+            },
+            (AFI::LinkState, SAFI::LinkState) => {
+                let mut nlri = Vec::new();
+                while buffer.remaining() > 0 {
+                    nlri.push(LinkStateNLRI::read(buffer)?);
+                }
+                Self::LinkState(nlri)
+            },
+            _ => Self::Unknown(buffer.read_bytes_vector(buffer.remaining())?)
+        })
+    }
+
+}
+
+#[repr(u8)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub enum Origin {
+    IGP = 0,
+    EGP = 1,
+    Incomplete = 2
+}
+
+impl Origin {
+
+    /// This function converts the value of the ORIGIN attribute. Undefined values are a Invalid
+    /// ORIGIN Attribute error, as defined in [RFC4271, Section 6.3](https://www.rfc-editor.org/rfc/rfc4271#section-6.3).
+    pub fn from(value: u8) -> Result<Self> {
+        match value {
+            0 => Ok(Self::IGP),
+            1 => Ok(Self::EGP),
+            2 => Ok(Self::Incomplete),
+            _ => Err(ErrorType::BGPError(BGPError::update(UpdateMessageError::InvalidOriginAttribute))
+                .err(format!("Unexpected value {value} of the ORIGIN attribute!")))
+        }
+    }
+
+}
+
+bitflags! {
+    #[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+    pub struct AttributeFlags: u8 {
+        const OPTIONAL        = 0b10000000;
+        const TRANSITIVE      = 0b01000000;
+        const PARTIAL         = 0b00100000;
+        const EXTENDED_LENGTH = 0b00010000;
+        const NONE            = 0b00000000;
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub enum AttributeType {
+    Reserved,
+    Origin,
+    ASPath,
+    NextHop,
+    MultiExitDisc,
+    LocalPref,
+    AtomicAggregate,
+    Aggregator,
+    Community,
+    OriginatorId,
+    ClusterList,
+    #[cfg(feature = "bgp_multiprotocol")]
+    MPReachableNLRI,
+    #[cfg(feature = "bgp_multiprotocol")]
+    MPUnreachableNLRI,
+    ExtendedCommunities,
+    AS4Path,
+    AS4Aggregator,
+    PMSITunnel,
+    TunnelEncapsulation,
+    TrafficEngineering,
+    Ipv6AddressSpecifiedExtendedCommunity,
+    AIGP,
+    PEDistinguisherLabels,
+    BGPLSAttribute,
+    LargeCommunity,
+    BGPSecPath,
+    OnlyToCustomer,
+    BGPDomainPath,
+    SFPAttribute,
+    BFDDiscriminator,
+    BGPRouterCapabilities,
+    BGPPrefixSID,
+    AttributeSet,
+    ReservedForDevelopment,
+    Unknown(u8)
+}
+
+impl AttributeType {
+
+    /// This function returns the approach to handle a malformed attribute of this type, as defined
+    /// in [RFC7606, Section 7](https://www.rfc-editor.org/rfc/rfc7606#section-7) and the RFCs of the
+    /// attributes. The default approach for all other attributes is Treat-as-withdraw.
+    pub fn error_action(&self) -> ErrorAction {
+        match self {
+            #[cfg(feature = "bgp_multiprotocol")]
+            Self::MPReachableNLRI | Self::MPUnreachableNLRI => ErrorAction::SessionReset,
+            Self::AtomicAggregate | Self::Aggregator | Self::AS4Path | Self::AS4Aggregator | Self::AIGP |
+            Self::BGPPrefixSID | Self::TunnelEncapsulation | Self::BGPLSAttribute => ErrorAction::AttributeDiscard,
+            _ => ErrorAction::TreatAsWithdraw
+        }
+    }
+
+    /// This function checks, if the attribute type may be carried in the ATTR_SET attribute. The
+    /// attributes of the reachability information and the ATTR_SET attribute itself are excluded, as
+    /// defined in [RFC6368, Section 5](https://www.rfc-editor.org/rfc/rfc6368#section-5).
+    pub fn is_allowed_in_attribute_set(&self) -> bool {
+        match self {
+            Self::NextHop | Self::AttributeSet => false,
+            #[cfg(feature = "bgp_multiprotocol")]
+            Self::MPReachableNLRI | Self::MPUnreachableNLRI => false,
+            _ => true
+        }
+    }
+
+    /// This function returns the expected Optional and Transitive bits of the attribute type, as
+    /// defined in the RFCs of the attributes. Types without a known definition return None.
+    pub fn expected_flags(&self) -> Option<AttributeFlags> {
+        let optional_transitive = AttributeFlags::OPTIONAL | AttributeFlags::TRANSITIVE;
+        Some(match self {
+            Self::Origin | Self::ASPath | Self::NextHop | Self::LocalPref | Self::AtomicAggregate => AttributeFlags::TRANSITIVE,
+            Self::MultiExitDisc | Self::OriginatorId | Self::ClusterList | Self::TrafficEngineering | Self::AIGP |
+            Self::BGPLSAttribute | Self::BGPSecPath => AttributeFlags::OPTIONAL,
+            #[cfg(feature = "bgp_multiprotocol")]
+            Self::MPReachableNLRI | Self::MPUnreachableNLRI => AttributeFlags::OPTIONAL,
+            Self::Aggregator | Self::Community | Self::ExtendedCommunities | Self::AS4Path | Self::AS4Aggregator |
+            Self::PMSITunnel | Self::TunnelEncapsulation | Self::Ipv6AddressSpecifiedExtendedCommunity |
+            Self::PEDistinguisherLabels | Self::LargeCommunity | Self::OnlyToCustomer | Self::BGPDomainPath |
+            Self::SFPAttribute | Self::BGPPrefixSID | Self::AttributeSet => optional_transitive,
+            _ => return None
+        })
+    }
+
+}
+
+impl From<u8> for AttributeType {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => Self::Reserved,
+            1 => Self::Origin,
+            2 => Self::ASPath,
+            3 => Self::NextHop,
+            4 => Self::MultiExitDisc,
+            5 => Self::LocalPref,
+            6 => Self::AtomicAggregate,
+            7 => Self::Aggregator,
+            8 => Self::Community,
+            9 => Self::OriginatorId,
+            10 => Self::ClusterList,
+            #[cfg(feature = "bgp_multiprotocol")]
+            14 => Self::MPReachableNLRI,
+            #[cfg(feature = "bgp_multiprotocol")]
+            15 => Self::MPUnreachableNLRI,
+            16 => Self::ExtendedCommunities,
+            17 => Self::AS4Path,
+            18 => Self::AS4Aggregator,
+            22 => Self::PMSITunnel,
+            23 => Self::TunnelEncapsulation,
+            24 => Self::TrafficEngineering,
+            25 => Self::Ipv6AddressSpecifiedExtendedCommunity,
+            26 => Self::AIGP,
+            27 => Self::PEDistinguisherLabels,
+            29 => Self::BGPLSAttribute,
+            32 => Self::LargeCommunity,
+            33 => Self::BGPSecPath,
+            35 => Self::OnlyToCustomer,
+            36 => Self::BGPDomainPath,
+            37 => Self::SFPAttribute,
+            38 => Self::BFDDiscriminator,
+            39 => Self::BGPRouterCapabilities,
+            40 => Self::BGPPrefixSID,
+            128 => Self::AttributeSet,
+            255 => Self::ReservedForDevelopment,
+            value => Self::Unknown(value)
+        }
+    }
+}
+
+impl From<AttributeType> for u8 {
+    fn from(value: AttributeType) -> Self {
+        match value {
+            AttributeType::Reserved => 0,
+            AttributeType::Origin => 1,
+            AttributeType::ASPath => 2,
+            AttributeType::NextHop => 3,
+            AttributeType::MultiExitDisc => 4,
+            AttributeType::LocalPref => 5,
+            AttributeType::AtomicAggregate => 6,
+            AttributeType::Aggregator => 7,
+            AttributeType::Community => 8,
+            AttributeType::OriginatorId => 9,
+            AttributeType::ClusterList => 10,
+            #[cfg(feature = "bgp_multiprotocol")]
+            AttributeType::MPReachableNLRI => 14,
+            #[cfg(feature = "bgp_multiprotocol")]
+            AttributeType::MPUnreachableNLRI => 15,
+            AttributeType::ExtendedCommunities => 16,
+            AttributeType::AS4Path => 17,
+            AttributeType::AS4Aggregator => 18,
+            AttributeType::PMSITunnel => 22,
+            AttributeType::TunnelEncapsulation => 23,
+            AttributeType::TrafficEngineering => 24,
+            AttributeType::Ipv6AddressSpecifiedExtendedCommunity => 25,
+            AttributeType::AIGP => 26,
+            AttributeType::PEDistinguisherLabels => 27,
+            AttributeType::BGPLSAttribute => 29,
+            AttributeType::LargeCommunity => 32,
+            AttributeType::BGPSecPath => 33,
+            AttributeType::OnlyToCustomer => 35,
+            AttributeType::BGPDomainPath => 36,
+            AttributeType::SFPAttribute => 37,
+            AttributeType::BFDDiscriminator => 38,
+            AttributeType::BGPRouterCapabilities => 39,
+            AttributeType::BGPPrefixSID => 40,
+            AttributeType::AttributeSet => 128,
+            AttributeType::ReservedForDevelopment => 255,
+            AttributeType::Unknown(value) => value
+        }
+    }
+}
+
+/// This is the representation of a community of the COMMUNITIES attribute, as defined in
+/// [RFC1997](https://www.rfc-editor.org/rfc/rfc1997). The community consists of the 2-byte AS number
+/// and a 2-byte value, that is defined by the AS. Communities are ordered by their numerical value.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Hash)]
+pub struct Community {
+    autonomous_system: u16,
+    value: u16
+}
+
+impl WriteRead for Community {
+    fn write(&self, buffer: &mut Buffer) -> Result<()> {
+        self.autonomous_system.write(buffer)?;
+        self.value.write(buffer)
+    }
+
+    fn read(buffer: &mut Buffer) -> Result<Self> where Self: Sized {
+        Ok(Self {
+            autonomous_system: u16::read(buffer)?,
+            value: u16::read(buffer)?
+        })
+    }
+}
+
+impl Community {
+
+    /// [RFC1997](https://www.rfc-editor.org/rfc/rfc1997): Routes must not be advertised outside a
+    /// BGP confederation boundary.
+    pub const NO_EXPORT: Self = Self::new(0xFFFF, 0xFF01);
+    /// [RFC1997](https://www.rfc-editor.org/rfc/rfc1997): Routes must not be advertised to any peer.
+    pub const NO_ADVERTISE: Self = Self::new(0xFFFF, 0xFF02);
+    /// [RFC1997](https://www.rfc-editor.org/rfc/rfc1997): Routes must not be advertised to external
+    /// peers, including peers in other member ASes of the confederation.
+    pub const NO_EXPORT_SUBCONFED: Self = Self::new(0xFFFF, 0xFF03);
+    /// [RFC3765](https://www.rfc-editor.org/rfc/rfc3765): Routes should not be advertised to other
+    /// peers of the AS.
+    pub const NO_PEER: Self = Self::new(0xFFFF, 0xFF04);
+    /// [RFC7999](https://www.rfc-editor.org/rfc/rfc7999): Traffic to the routes should be discarded.
+    pub const BLACKHOLE: Self = Self::new(0xFFFF, 666);
+    /// [RFC8326](https://www.rfc-editor.org/rfc/rfc8326): The routes are withdrawn soon due to a
+    /// graceful shutdown of the session.
+    pub const GRACEFUL_SHUTDOWN: Self = Self::new(0xFFFF, 0);
+
+    const NAMES: [(Self, &'static str); 6] = [
+        (Self::NO_EXPORT, "no-export"),
+        (Self::NO_ADVERTISE, "no-advertise"),
+        (Self::NO_EXPORT_SUBCONFED, "no-export-subconfed"),
+        (Self::NO_PEER, "no-peer"),
+        (Self::BLACKHOLE, "blackhole"),
+        (Self::GRACEFUL_SHUTDOWN, "graceful-shutdown")
+    ];
+
+    pub const fn new(autonomous_system: u16, value: u16) -> Self {
+        Self {
+            autonomous_system,
+            value
+        }
+    }
+
+    pub fn autonomous_system(&self) -> u16 {
+        self.autonomous_system
+    }
+
+    pub fn value(&self) -> u16 {
+        self.value
+    }
+
+    /// This function returns true, if the community is in the range 0xFFFF0000 to 0xFFFFFFFF, which
+    /// is reserved for well-known communities.
+    pub fn is_well_known(&self) -> bool {
+        self.autonomous_system == 0xFFFF
+    }
+
+    /// This function returns the name of the well-known community, if the name is known.
+    pub fn name(&self) -> Option<&'static str> {
+        Self::NAMES.iter().find(|(community, _)| community == self).map(|(_, name)| *name)
+    }
+
+}
+
+impl From<u32> for Community {
+    fn from(value: u32) -> Self {
+        Self::new((value >> 16) as u16, value as u16)
+    }
+}
+
+impl From<Community> for u32 {
+    fn from(value: Community) -> Self {
+        ((value.autonomous_system as u32) << 16) | value.value as u32
+    }
+}
+
+/// The community is formatted as `<as>:<value>` or with the name of the well-known community.
+impl Display for Community {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> core::fmt::Result {
+        match self.name() {
+            Some(name) => write!(formatter, "{name}"),
+            None => write!(formatter, "{}:{}", self.autonomous_system, self.value)
+        }
+    }
+}
+
+impl FromStr for Community {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        if let Some((community, _)) = Self::NAMES.iter().find(|(_, name)| name.eq_ignore_ascii_case(value)) {
+            return Ok(*community);
+        }
+
+        let invalid = || ErrorType::ParseError.err(format!("Unable to parse community '{value}'! Expected <as>:<value> or the name of a well-known community"));
+        let (autonomous_system, community_value) = value.split_once(':').ok_or_else(invalid)?;
+        Ok(Self::new(
+            u16::from_str(autonomous_system).map_err(|_| invalid())?,
+            u16::from_str(community_value).map_err(|_| invalid())?
+        ))
+    }
+}
+
+/// This is the representation of a community of the LARGE_COMMUNITY attribute, as defined in
+/// [RFC8092](https://www.rfc-editor.org/rfc/rfc8092). The community consists of the 4-byte AS
+/// number of the global administrator and two 4-byte values, that are defined by the AS.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Hash)]
+pub struct LargeCommunity {
+    global_administrator: u32,
+    local_data_part_1: u32,
+    local_data_part_2: u32
+}
+
+impl WriteRead for LargeCommunity {
+    fn write(&self, buffer: &mut Buffer) -> Result<()> {
+        self.global_administrator.write(buffer)?;
+        self.local_data_part_1.write(buffer)?;
+        self.local_data_part_2.write(buffer)
+    }
+
+    fn read(buffer: &mut Buffer) -> Result<Self> where Self: Sized {
+        Ok(Self {
+            global_administrator: u32::read(buffer)?,
+            local_data_part_1: u32::read(buffer)?,
+            local_data_part_2: u32::read(buffer)?
+        })
+    }
+}
+
+impl LargeCommunity {
+
+    pub const fn new(global_administrator: u32, local_data_part_1: u32, local_data_part_2: u32) -> Self {
+        Self {
+            global_administrator,
+            local_data_part_1,
+            local_data_part_2
+        }
+    }
+
+    /// This function creates a large community of the global administrator with the function and
+    /// the parameter of the function in the local data parts.
+    pub fn with_function(global_administrator: u32, function: LargeCommunityFunction) -> Self {
+        let (function, parameter) = function.into();
+        Self::new(global_administrator, function, parameter)
+    }
+
+    pub fn global_administrator(&self) -> u32 {
+        self.global_administrator
+    }
+
+    pub fn local_data_part_1(&self) -> u32 {
+        self.local_data_part_1
+    }
+
+    pub fn local_data_part_2(&self) -> u32 {
+        self.local_data_part_2
+    }
+
+    /// This function returns the function of the community, if the local data parts follow the
+    /// conventions of [RFC8195](https://www.rfc-editor.org/rfc/rfc8195).
+    pub fn function(&self) -> LargeCommunityFunction {
+        LargeCommunityFunction::from((self.local_data_part_1, self.local_data_part_2))
+    }
+
+}
+
+/// The large community is formatted as `<global>:<local1>:<local2>`, as described in
+/// [RFC8092, Section 5](https://www.rfc-editor.org/rfc/rfc8092#section-5).
+impl Display for LargeCommunity {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(formatter, "{}:{}:{}", self.global_administrator, self.local_data_part_1, self.local_data_part_2)
+    }
+}
+
+impl FromStr for LargeCommunity {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        let invalid = || ErrorType::ParseError.err(format!("Unable to parse large community '{value}'! Expected <global>:<local1>:<local2>"));
+        let mut parts = value.split(':').map(|part| u32::from_str(part).map_err(|_| invalid()));
+        let community = Self::new(
+            parts.next().ok_or_else(invalid)??,
+            parts.next().ok_or_else(invalid)??,
+            parts.next().ok_or_else(invalid)??
+        );
+
+        if parts.next().is_some() {
+            return Err(invalid());
+        }
+        Ok(community)
+    }
+}
+
+/// This is the representation of the function of a large community, which is encoded in the first
+/// local data part with the parameter in the second local data part. The functions follow the
+/// conventions of [RFC8195](https://www.rfc-editor.org/rfc/rfc8195), that are used by many
+/// operators. Informational communities are set by the operator, action communities are set by
+/// the customers to request an action of the operator.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub enum LargeCommunityFunction {
+    /// The route was learned in the country with the ISO 3166-1 numeric code.
+    Country(u32),
+    /// The route was learned in the region with the UN M.49 code.
+    Region(u32),
+    /// The route must not be advertised to the peer AS.
+    NoExportToAS(u32),
+    /// The route must not be advertised in the country with the ISO 3166-1 numeric code.
+    NoExportInCountry(u32),
+    /// The route must not be advertised in the region with the UN M.49 code.
+    NoExportInRegion(u32),
+    /// The AS of the operator is prepended once, if the route is advertised to the peer AS.
+    PrependToAS(u32),
+    /// The AS of the operator is prepended once, if the route is advertised in the country.
+    PrependInCountry(u32),
+    /// The AS of the operator is prepended once, if the route is advertised in the region.
+    PrependInRegion(u32),
+    Unknown(u32, u32)
+}
+
+impl LargeCommunityFunction {
+
+    /// This function returns true, if the function is a action community, that has to be executed
+    /// by the operator.
+    pub fn is_action(&self) -> bool {
+        !matches!(self, Self::Country(_) | Self::Region(_) | Self::Unknown(..))
+    }
+
+}
+
+impl From<(u32, u32)> for LargeCommunityFunction {
+    fn from((function, parameter): (u32, u32)) -> Self {
+        match function {
+            1 => Self::Country(parameter),
+            2 => Self::Region(parameter),
+            4 => Self::NoExportToAS(parameter),
+            5 => Self::NoExportInCountry(parameter),
+            6 => Self::NoExportInRegion(parameter),
+            7 => Self::PrependToAS(parameter),
+            8 => Self::PrependInCountry(parameter),
+            9 => Self::PrependInRegion(parameter),
+            _ => Self::Unknown(function, parameter)
+        }
+    }
+}
+
+impl From<LargeCommunityFunction> for (u32, u32) {
+    fn from(value: LargeCommunityFunction) -> Self {
+        match value {
+            LargeCommunityFunction::Country(parameter) => (1, parameter),
+            LargeCommunityFunction::Region(parameter) => (2, parameter),
+            LargeCommunityFunction::NoExportToAS(parameter) => (4, parameter),
+            LargeCommunityFunction::NoExportInCountry(parameter) => (5, parameter),
+            LargeCommunityFunction::NoExportInRegion(parameter) => (6, parameter),
+            LargeCommunityFunction::PrependToAS(parameter) => (7, parameter),
+            LargeCommunityFunction::PrependInCountry(parameter) => (8, parameter),
+            LargeCommunityFunction::PrependInRegion(parameter) => (9, parameter),
+            LargeCommunityFunction::Unknown(function, parameter) => (function, parameter)
+        }
+    }
+}
+
+/// This is the representation of the sub-type of an extended community, as defined in
+/// [RFC4360, Section 5](https://www.rfc-editor.org/rfc/rfc4360#section-5). The sub-type is only
+/// interpreted for the two-octet AS, IPv4 address and four-octet AS specific types.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub enum ExtendedCommunitySubType {
+    /// This sub-type identifies a set of sites that may receive the routes tagged with this
+    /// community (Route Target).
+    RouteTarget,
+
+    /// This sub-type identifies the site or sites from which the router learned the route (Route
+    /// Origin).
+    RouteOrigin,
+
+    /// This is only the representation for a unknown sub-type
+    Unknown(u8)
+}
+
+impl From<u8> for ExtendedCommunitySubType {
+    fn from(value: u8) -> Self {
+        match value {
+            0x02 => Self::RouteTarget,
+            0x03 => Self::RouteOrigin,
+            value => Self::Unknown(value)
+        }
+    }
+}
+
+impl From<ExtendedCommunitySubType> for u8 {
+    fn from(value: ExtendedCommunitySubType) -> Self {
+        match value {
+            ExtendedCommunitySubType::RouteTarget => 0x02,
+            ExtendedCommunitySubType::RouteOrigin => 0x03,
+            ExtendedCommunitySubType::Unknown(value) => value
+        }
+    }
+}
+
+/// This is the representation of the 8-byte extended community defined in
+/// [RFC4360](https://www.rfc-editor.org/rfc/rfc4360) and
+/// [RFC5668](https://www.rfc-editor.org/rfc/rfc5668). The first byte of the community defines the
+/// type and the transitivity of the community. The layout of the community is shown below:
+/// ```text
+/// 0                   1                   2                   3
+/// 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |  Type high    |  Type low(*)  |                               |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+          Value                |
+/// |                                                               |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// ```
+///
+/// ## Text representation
+/// The route target and route origin communities of the transitive types can be formatted and
+/// parsed in the usual text forms like `rt:65000:100`, `ro:192.0.2.1:100` or `rt:4200000000:100`.
+/// A four-octet AS with a value lower than 65536 is suffixed with `L` (`rt:65000L:100`). All
+/// other communities are formatted as hexadecimal value like `0x0002fde800000064`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub enum ExtendedCommunity {
+    /// This is the two-octet AS specific community (Type 0x00 or 0x40) with the transitivity, the
+    /// sub-type, the 2-byte global administrator (AS) and the 4-byte local administrator.
+    TwoOctetAS(bool, ExtendedCommunitySubType, u16, u32),
+
+    /// This is the IPv4 address specific community (Type 0x01 or 0x41) with the transitivity, the
+    /// sub-type, the IPv4 address as global administrator and the 2-byte local administrator.
+    IPv4Address(bool, ExtendedCommunitySubType, Ipv4Addr, u16),
+
+    /// This is the four-octet AS specific community (Type 0x02 or 0x42) with the transitivity, the
+    /// sub-type, the 4-byte global administrator (AS) and the 2-byte local administrator.
+    FourOctetAS(bool, ExtendedCommunitySubType, u32, u16),
+
+    /// This is the opaque community (Type 0x03 or 0x43) with the transitivity, the sub-type and the
+    /// 6-byte value.
+    Opaque(bool, u8, [u8; 6]),
+
+    /// This is only the representation for a community with a unknown type. The community is
+    /// preserved as raw 8 bytes.
+    Unknown([u8; 8])
+}
+
+impl WriteRead for ExtendedCommunity {
+    fn write(&self, buffer: &mut Buffer) -> Result<()> {
+        buffer.write_bytes_array(self.to_bytes());
+        Ok(())
+    }
+
+    fn read(buffer: &mut Buffer) -> Result<Self> where Self: Sized {
+        Ok(Self::from(buffer.read_bytes_array::<8>()?))
+    }
+}
+
+impl From<[u8; 8]> for ExtendedCommunity {
+    fn from(value: [u8; 8]) -> Self {
+        let transitive = value[0] & 0x40 == 0;
+        let sub_type = ExtendedCommunitySubType::from(value[1]);
+        match value[0] & !0x40 {
+            0x00 => Self::TwoOctetAS(
+                transitive,
+                sub_type,
+                u16::from_be_bytes([value[2], value[3]]),
+                u32::from_be_bytes([value[4], value[5], value[6], value[7]])
+            ),
+            0x01 => Self::IPv4Address(
+                transitive,
+                sub_type,
+                Ipv4Addr::new(value[2], value[3], value[4], value[5]),
+                u16::from_be_bytes([value[6], value[7]])
+            ),
+            0x02 => Self::FourOctetAS(
+                transitive,
+                sub_type,
+                u32::from_be_bytes([value[2], value[3], value[4], value[5]]),
+                u16::from_be_bytes([value[6], value[7]])
+            ),
+            0x03 => Self::Opaque(
+                transitive,
+                value[1],
+                [value[2], value[3], value[4], value[5], value[6], value[7]]
+            ),
+            _ => Self::Unknown(value)
+        }
+    }
+}
+
+impl Display for ExtendedCommunity {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> core::fmt::Result {
+        let name = match self.sub_type() {
+            Some(ExtendedCommunitySubType::RouteTarget) if self.is_transitive() => Some("rt"),
+            Some(ExtendedCommunitySubType::RouteOrigin) if self.is_transitive() => Some("ro"),
+            _ => None
+        };
+
+        match (self, name) {
+            (Self::TwoOctetAS(_, _, global_administrator, local_administrator), Some(name)) => write!(formatter, "{name}:{global_administrator}:{local_administrator}"),
+            (Self::IPv4Address(_, _, global_administrator, local_administrator), Some(name)) => write!(formatter, "{name}:{global_administrator}:{local_administrator}"),
+            (Self::FourOctetAS(_, _, global_administrator, local_administrator), Some(name)) if *global_administrator <= u16::MAX as u32 => {
+                write!(formatter, "{name}:{global_administrator}L:{local_administrator}")
+            },
+            (Self::FourOctetAS(_, _, global_administrator, local_administrator), Some(name)) => write!(formatter, "{name}:{global_administrator}:{local_administrator}"),
+            _ => write!(formatter, "0x{:016x}", u64::from_be_bytes(self.to_bytes()))
+        }
+    }
+}
+
+impl FromStr for ExtendedCommunity {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        let invalid = || ErrorType::ParseError.err(format!("Unable to parse extended community '{value}'! Expected rt:<global>:<local>, ro:<global>:<local> or a hexadecimal value"));
+        if let Some(hex) = value.strip_prefix("0x") {
+            return Ok(Self::from(u64::from_str_radix(hex, 16).map_err(|_| invalid())?.to_be_bytes()));
+        }
+
+        let (name, rest) = value.split_once(':').ok_or_else(invalid)?;
+        let sub_type = match name {
+            "rt" | "target" => ExtendedCommunitySubType::RouteTarget,
+            "ro" | "origin" => ExtendedCommunitySubType::RouteOrigin,
+            _ => return Err(invalid())
+        };
+
+        let (global_administrator, local_administrator) = rest.rsplit_once(':').ok_or_else(invalid)?;
+        if let Ok(address) = Ipv4Addr::from_str(global_administrator) {
+            let local_administrator = u16::from_str(local_administrator).map_err(|_| invalid())?;
+            return Ok(Self::IPv4Address(true, sub_type, address, local_administrator));
+        }
+
+        if let Some(global_administrator) = global_administrator.strip_suffix('L') {
+            let global_administrator = u32::from_str(global_administrator).map_err(|_| invalid())?;
+            let local_administrator = u16::from_str(local_administrator).map_err(|_| invalid())?;
+            return Ok(Self::FourOctetAS(true, sub_type, global_administrator, local_administrator));
+        }
+
+        let global_administrator = u32::from_str(global_administrator).map_err(|_| invalid())?;
+        match u16::try_from(global_administrator) {
+            Ok(global_administrator) => {
+                let local_administrator = u32::from_str(local_administrator).map_err(|_| invalid())?;
+                Ok(Self::TwoOctetAS(true, sub_type, global_administrator, local_administrator))
+            },
+            Err(_) => {
+                let local_administrator = u16::from_str(local_administrator).map_err(|_| invalid())?;
+                Ok(Self::FourOctetAS(true, sub_type, global_administrator, local_administrator))
+            }
+        }
+    }
+}
+
+impl ExtendedCommunity {
+
+    /// This function returns the 8-byte wire representation of the extended community.
+    pub fn to_bytes(&self) -> [u8; 8] {
+        let type_high = |base: u8, transitive: bool| if transitive { base } else { base | 0x40 };
+        let mut bytes = [0; 8];
+        match self {
+            Self::TwoOctetAS(transitive, sub_type, global_administrator, local_administrator) => {
+                bytes[0] = type_high(0x00, *transitive);
+                bytes[1] = u8::from(*sub_type);
+                bytes[2..4].copy_from_slice(&global_administrator.to_be_bytes());
+                bytes[4..8].copy_from_slice(&local_administrator.to_be_bytes());
+            },
+            Self::IPv4Address(transitive, sub_type, global_administrator, local_administrator) => {
+                bytes[0] = type_high(0x01, *transitive);
+                bytes[1] = u8::from(*sub_type);
+                bytes[2..6].copy_from_slice(&global_administrator.octets());
+                bytes[6..8].copy_from_slice(&local_administrator.to_be_bytes());
+            },
+            Self::FourOctetAS(transitive, sub_type, global_administrator, local_administrator) => {
+                bytes[0] = type_high(0x02, *transitive);
+                bytes[1] = u8::from(*sub_type);
+                bytes[2..6].copy_from_slice(&global_administrator.to_be_bytes());
+                bytes[6..8].copy_from_slice(&local_administrator.to_be_bytes());
+            },
+            Self::Opaque(transitive, sub_type, value) => {
+                bytes[0] = type_high(0x03, *transitive);
+                bytes[1] = *sub_type;
+                bytes[2..8].copy_from_slice(value);
+            },
+            Self::Unknown(value) => bytes = *value
+        }
+        bytes
+    }
+
+    /// This function returns the sub-type of the two-octet AS, IPv4 address and four-octet AS
+    /// specific communities. For all other types, this function returns none.
+    pub fn sub_type(&self) -> Option<ExtendedCommunitySubType> {
+        match self {
+            Self::TwoOctetAS(_, sub_type, _, _)
+            | Self::IPv4Address(_, sub_type, _, _)
+            | Self::FourOctetAS(_, sub_type, _, _) => Some(*sub_type),
+            _ => None
+        }
+    }
+
+    pub fn is_transitive(&self) -> bool {
+        self.to_bytes()[0] & 0x40 == 0
+    }
+
+    pub fn is_route_target(&self) -> bool {
+        self.sub_type() == Some(ExtendedCommunitySubType::RouteTarget)
+    }
+
+    pub fn is_route_origin(&self) -> bool {
+        self.sub_type() == Some(ExtendedCommunitySubType::RouteOrigin)
+    }
+
+}
+
+/// This is the representation of the 20-byte IPv6 address specific extended community defined in
+/// [RFC5701](https://www.rfc-editor.org/rfc/rfc5701). The layout of the community is shown below:
+/// ```text
+/// 0                   1                   2                   3
+/// 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// | 0x00 or 0x40  |    Sub-Type   |    Global Administrator       |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |          Global Administrator (cont.)                         |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |          Global Administrator (cont.)                         |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |          Global Administrator (cont.)                         |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// | Global Administrator (cont.)  |    Local Administrator        |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// ```
+///
+/// ## Text representation
+/// The transitive route target and route origin communities are formatted and parsed like
+/// `rt:[2001:db8::1]:100` or `ro:[2001:db8::1]:100`. The brackets are optional while parsing. All
+/// other communities are formatted as hexadecimal value.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub enum Ipv6ExtendedCommunity {
+    /// This is the IPv6 address specific community (Type 0x00 or 0x40) with the transitivity, the
+    /// sub-type, the IPv6 address as global administrator and the 2-byte local administrator.
+    IPv6Address(bool, ExtendedCommunitySubType, Ipv6Addr, u16),
+
+    /// This is only the representation for a community with a unknown type. The community is
+    /// preserved as raw 20 bytes.
+    Unknown([u8; 20])
+}
+
+impl WriteRead for Ipv6ExtendedCommunity {
+    fn write(&self, buffer: &mut Buffer) -> Result<()> {
+        buffer.write_bytes_array(self.to_bytes());
+        Ok(())
+    }
+
+    fn read(buffer: &mut Buffer) -> Result<Self> where Self: Sized {
+        Ok(Self::from(buffer.read_bytes_array::<20>()?))
+    }
+}
+
+impl From<[u8; 20]> for Ipv6ExtendedCommunity {
+    fn from(value: [u8; 20]) -> Self {
+        if value[0] & !0x40 != 0x00 {
+            return Self::Unknown(value);
+        }
+
+        let mut global_administrator = [0; 16];
+        global_administrator.copy_from_slice(&value[2..18]);
+        Self::IPv6Address(
+            value[0] & 0x40 == 0,
+            ExtendedCommunitySubType::from(value[1]),
+            Ipv6Addr::from(global_administrator),
+            u16::from_be_bytes([value[18], value[19]])
+        )
+    }
+}
+
+impl Display for Ipv6ExtendedCommunity {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::IPv6Address(true, ExtendedCommunitySubType::RouteTarget, global_administrator, local_administrator) => {
+                write!(formatter, "rt:[{global_administrator}]:{local_administrator}")
+            },
+            Self::IPv6Address(true, ExtendedCommunitySubType::RouteOrigin, global_administrator, local_administrator) => {
+                write!(formatter, "ro:[{global_administrator}]:{local_administrator}")
+            },
+            _ => {
+                formatter.write_str("0x")?;
+                for byte in self.to_bytes() {
+                    write!(formatter, "{byte:02x}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl FromStr for Ipv6ExtendedCommunity {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        let invalid = || ErrorType::ParseError.err(format!("Unable to parse IPv6 address specific extended community '{value}'! Expected rt:[<address>]:<local>, ro:[<address>]:<local> or a hexadecimal value"));
+        if let Some(hex) = value.strip_prefix("0x") {
+            if hex.len() != 40 || !hex.is_ascii() {
+                return Err(invalid());
+            }
+
+            let mut bytes = [0; 20];
+            for (index, byte) in bytes.iter_mut().enumerate() {
+                *byte = u8::from_str_radix(&hex[index * 2..index * 2 + 2], 16).map_err(|_| invalid())?;
+            }
+            return Ok(Self::from(bytes));
+        }
+
+        let (name, rest) = value.split_once(':').ok_or_else(invalid)?;
+        let sub_type = match name {
+            "rt" | "target" => ExtendedCommunitySubType::RouteTarget,
+            "ro" | "origin" => ExtendedCommunitySubType::RouteOrigin,
+            _ => return Err(invalid())
+        };
+
+        let (global_administrator, local_administrator) = rest.rsplit_once(':').ok_or_else(invalid)?;
+        let global_administrator = global_administrator
+            .strip_prefix('[')
+            .and_then(|address| address.strip_suffix(']'))
+            .unwrap_or(global_administrator);
+        Ok(Self::new(
+            true,
+            sub_type,
+            Ipv6Addr::from_str(global_administrator).map_err(|_| invalid())?,
+            u16::from_str(local_administrator).map_err(|_| invalid())?
+        ))
+    }
+}
+
+impl Ipv6ExtendedCommunity {
+
+    pub fn new(transitive: bool, sub_type: ExtendedCommunitySubType, global_administrator: Ipv6Addr, local_administrator: u16) -> Self {
+        Self::IPv6Address(transitive, sub_type, global_administrator, local_administrator)
+    }
+
+    /// This function returns the 20-byte wire representation of the IPv6 address specific
+    /// extended community.
+    pub fn to_bytes(&self) -> [u8; 20] {
+        match self {
+            Self::IPv6Address(transitive, sub_type, global_administrator, local_administrator) => {
+                let mut bytes = [0; 20];
+                bytes[0] = if *transitive { 0x00 } else { 0x40 };
+                bytes[1] = u8::from(*sub_type);
+                bytes[2..18].copy_from_slice(&global_administrator.octets());
+                bytes[18..20].copy_from_slice(&local_administrator.to_be_bytes());
+                bytes
+            },
+            Self::Unknown(value) => *value
+        }
+    }
+
+    pub fn is_transitive(&self) -> bool {
+        self.to_bytes()[0] & 0x40 == 0
+    }
+
+    /// This function returns the sub-type of the IPv6 address specific community. For unknown
+    /// types, this function returns none.
+    pub fn sub_type(&self) -> Option<ExtendedCommunitySubType> {
+        match self {
+            Self::IPv6Address(_, sub_type, _, _) => Some(*sub_type),
+            Self::Unknown(_) => None
+        }
+    }
+
+    pub fn global_administrator(&self) -> Option<Ipv6Addr> {
+        match self {
+            Self::IPv6Address(_, _, global_administrator, _) => Some(*global_administrator),
+            Self::Unknown(_) => None
+        }
+    }
+
+    pub fn local_administrator(&self) -> Option<u16> {
+        match self {
+            Self::IPv6Address(_, _, _, local_administrator) => Some(*local_administrator),
+            Self::Unknown(_) => None
+        }
+    }
+
+}
+
+/// This is the representation of the AS_PATH and AS4_PATH attributes. The path is a list of
+/// segments, which are defined in [RFC4271, Section 4.3](https://www.rfc-editor.org/rfc/rfc4271#section-4.3)
+/// and [RFC5065, Section 3](https://www.rfc-editor.org/rfc/rfc5065#section-3). Every segment is
+/// encoded with the following layout:
+/// ```text
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// | Segment Type  | Segment Length|   AS Numbers (variable)       |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// ```
+///
+/// A segment on the wire contains at most 255 AS numbers. Longer sequences of the path are split
+/// into multiple segments while writing and consecutive sequences are merged while reading. Sets
+/// aren't split, because every set counts as a single AS in the length of the path.
+#[derive(Clone, PartialEq, Eq, Debug, Hash, Default)]
+pub struct ASPath {
+    segments: Vec<ASPathSegment>
+}
+
+impl WriteRead for ASPath {
+    fn write(&self, buffer: &mut Buffer) -> Result<()> {
+        self.write_with(buffer, &SessionParameters::default())
+    }
+
+    fn read(buffer: &mut Buffer) -> Result<Self> where Self: Sized {
+        Self::read_with(buffer, &SessionParameters::default())
+    }
+}
+
+impl ASPath {
+
+    pub fn new(segments: Vec<ASPathSegment>) -> Self {
+        let mut path = Self { segments };
+        path.merge_sequences();
+        path
+    }
+
+    /// This function writes all segments of the path into the buffer. If the four-octet AS number
+    /// support isn't negotiated with the peer, all AS numbers are written as 2-byte AS numbers.
+    pub fn write_with(&self, buffer: &mut Buffer, parameters: &SessionParameters) -> Result<()> {
+        for segment in &self.segments {
+            // RFC4271, Section 9.1.2.2: Splitting a set would increase the length of the path
+            if matches!(segment, ASPathSegment::ASSet(_) | ASPathSegment::ASConfedSet(_)) && segment.values().len() > u8::MAX as usize {
+                return Err(ErrorType::WriteError.err(format!("Unable to write AS path! The set contains {} AS numbers, but at most 255 AS numbers are allowed", segment.values().len())));
+            }
+            for values in segment.values().chunks(u8::MAX as usize) {
+                u8::from(segment).write(buffer)?;
+                (values.len() as u8).write(buffer)?;
+                for value in values {
+                    if parameters.four_octet_as {
+                        value.write(buffer)?;
+                    } else {
+                        two_octet_as(*value)?.write(buffer)?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// This function reads all segments of the path from the buffer until the end of the buffer.
+    /// If the four-octet AS number support isn't negotiated with the peer, all AS numbers are read
+    /// as 2-byte AS numbers.
+    pub fn read_with(buffer: &mut Buffer, parameters: &SessionParameters) -> Result<Self> {
+        let malformed = |message: String| ErrorType::BGPError(BGPError::update(UpdateMessageError::MalformedASPath)).err(message);
+        let as_length = if parameters.four_octet_as { 4 } else { 2 };
+
+        let mut segments = Vec::new();
+        while buffer.remaining() > 0 {
+            let ty = u8::read(buffer)?;
+            let length = u8::read(buffer).map_err(|_| malformed(format!("Unexpected end of AS path segment with type {ty}!")))?;
+            if length == 0 || buffer.remaining() < length as usize * as_length {
+                return Err(malformed(format!("Unexpected length of AS path segment! Got {length} AS numbers with {} remaining bytes", buffer.remaining())));
+            }
+
+            let mut values = Vec::new();
+            for _ in 0..length {
+                values.push(if parameters.four_octet_as {
+                    u32::read(buffer)?
+                } else {
+                    u16::read(buffer)? as u32
+                });
+            }
+
+            segments.push(match ty {
+                1 => ASPathSegment::ASSet(values),
+                2 => ASPathSegment::ASSequence(values),
+                3 => ASPathSegment::ASConfedSequence(values),
+                4 => ASPathSegment::ASConfedSet(values),
+                ty => return Err(malformed(format!("Unexpected AS path segment type! Got {ty} but expected 1 to 4")))
+            });
+        }
+        Ok(Self::new(segments))
+    }
+
+    pub fn segments(&self) -> &[ASPathSegment] {
+        &self.segments
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.segments.is_empty()
+    }
+
+    /// This function calculates the length of the path, which is used in the decision process. As
+    /// described in [RFC4271, Section 9.1.2.2](https://www.rfc-editor.org/rfc/rfc4271#section-9.1.2.2),
+    /// an AS_SET counts as 1, no matter how many AS numbers are in the set. As described in
+    /// [RFC5065, Section 5.3](https://www.rfc-editor.org/rfc/rfc5065#section-5.3), the
+    /// confederation segments are not counted.
+    pub fn path_length(&self) -> usize {
+        self.segments.iter().map(|segment| match segment {
+            ASPathSegment::ASSequence(values) => values.len(),
+            ASPathSegment::ASSet(values) => usize::from(!values.is_empty()),
+            ASPathSegment::ASConfedSequence(_) | ASPathSegment::ASConfedSet(_) => 0
+        }).sum()
+    }
+
+    /// This function checks if the AS number is contained by any segment of the path. This is used
+    /// to detect loops, as described in [RFC4271, Section 9.1.2](https://www.rfc-editor.org/rfc/rfc4271#section-9.1.2).
+    pub fn contains(&self, autonomous_system: u32) -> bool {
+        self.segments.iter().any(|segment| segment.values().contains(&autonomous_system))
+    }
+
+    /// This function prepends the AS number to the path, before the route is advertised to an
+    /// external peer. The AS number is prepended to the first AS_SEQUENCE or a new AS_SEQUENCE is
+    /// created, if the path doesn't begin with an AS_SEQUENCE.
+    pub fn prepend(&mut self, autonomous_system: u32) {
+        match self.segments.first_mut() {
+            Some(ASPathSegment::ASSequence(values)) => values.insert(0, autonomous_system),
+            _ => self.segments.insert(0, ASPathSegment::ASSequence(vec![autonomous_system]))
+        }
+    }
+
+    /// This function prepends the member AS number to the path, before the route is advertised to
+    /// a peer in another member AS of the confederation. This is described in
+    /// [RFC5065, Section 5.1](https://www.rfc-editor.org/rfc/rfc5065#section-5.1).
+    pub fn prepend_confederation(&mut self, autonomous_system: u32) {
+        match self.segments.first_mut() {
+            Some(ASPathSegment::ASConfedSequence(values)) => values.insert(0, autonomous_system),
+            _ => self.segments.insert(0, ASPathSegment::ASConfedSequence(vec![autonomous_system]))
+        }
+    }
+
+    /// This function removes all confederation segments from the path, before the route is
+    /// advertised to a peer outside of the confederation. This is described in
+    /// [RFC5065, Section 5.1](https://www.rfc-editor.org/rfc/rfc5065#section-5.1).
+    pub fn remove_confederation_segments(&mut self) {
+        self.segments.retain(|segment| !matches!(segment, ASPathSegment::ASConfedSequence(_) | ASPathSegment::ASConfedSet(_)));
+        self.merge_sequences();
+    }
+
+    fn merge_sequences(&mut self) {
+        let mut segments: Vec<ASPathSegment> = Vec::with_capacity(self.segments.len());
+        for segment in self.segments.drain(..) {
+            match (segments.last_mut(), segment) {
+                (Some(ASPathSegment::ASSequence(values)), ASPathSegment::ASSequence(mut next))
+                | (Some(ASPathSegment::ASConfedSequence(values)), ASPathSegment::ASConfedSequence(mut next)) => values.append(&mut next),
+                (_, segment) => segments.push(segment)
+            }
+        }
+        self.segments = segments;
+    }
+
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Hash)]
+pub enum ASPathSegment {
+    /// This is a unordered set of AS numbers, which the route has traversed (Type 1).
+    ASSet(Vec<u32>),
+
+    /// This is a ordered sequence of AS numbers, which the route has traversed (Type 2).
+    ASSequence(Vec<u32>),
+
+    /// This is a ordered sequence of member AS numbers in the local confederation, which the route
+    /// has traversed (Type 3).
+    ASConfedSequence(Vec<u32>),
+
+    /// This is a unordered set of member AS numbers in the local confederation, which the route
+    /// has traversed (Type 4).
+    ASConfedSet(Vec<u32>)
+}
+
+impl ASPathSegment {
+
+    pub fn values(&self) -> &[u32] {
+        match self {
+            Self::ASSet(values)
+            | Self::ASSequence(values)
+            | Self::ASConfedSequence(values)
+            | Self::ASConfedSet(values) => values
+        }
+    }
+
+    pub fn values_mut(&mut self) -> &mut Vec<u32> {
+        match self {
+            Self::ASSet(values)
+            | Self::ASSequence(values)
+            | Self::ASConfedSequence(values)
+            | Self::ASConfedSet(values) => values
+        }
+    }
+
+}
+
+impl From<&ASPathSegment> for u8 {
+    fn from(value: &ASPathSegment) -> Self {
+        match value {
+            ASPathSegment::ASSet(_) => 1,
+            ASPathSegment::ASSequence(_) => 2,
+            ASPathSegment::ASConfedSequence(_) => 3,
+            ASPathSegment::ASConfedSet(_) => 4
+        }
+    }
+}
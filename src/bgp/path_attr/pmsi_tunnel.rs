@@ -0,0 +1,202 @@
+use bitflags::bitflags;
+use crate::bgp::error::{BGPError, UpdateMessageError};
+use crate::error::{Error, ErrorType};
+use crate::if_no_std;
+use crate::io::{Buffer, WriteRead};
+use crate::Result;
+use core::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+if_no_std! {
+    use alloc::{format, vec::Vec};
+}
+
+/// This is the representation of the PMSI Tunnel attribute, as defined in
+/// [RFC6514, Section 5](https://www.rfc-editor.org/rfc/rfc6514#section-5). The attribute
+/// describes the tunnel of a Provider Multicast Service Interface, which is used by multicast VPN
+/// and EVPN inclusive multicast routes.
+///
+/// The label is the 3-byte MPLS Label field, which contains the 20-bit label in the high-order
+/// bits. EVPN with VXLAN encapsulation uses the whole field as VNI, as described in
+/// [RFC8365, Section 5.1.3](https://www.rfc-editor.org/rfc/rfc8365#section-5.1.3).
+#[derive(Clone, PartialEq, Eq, Debug, Hash)]
+pub struct PMSITunnel {
+    pub flags: PMSITunnelFlags,
+    pub label: u32,
+    pub tunnel_identifier: PMSITunnelIdentifier
+}
+
+impl WriteRead for PMSITunnel {
+    fn write(&self, buffer: &mut Buffer) -> Result<()> {
+        if self.label > 0xFFFFFF {
+            return Err(ErrorType::WriteError.err(format!("Unable to write MPLS label {}! The label is limited to 3 bytes", self.label)));
+        }
+        self.flags.bits().write(buffer)?;
+        self.tunnel_identifier.tunnel_type().write(buffer)?;
+        buffer.write_bytes_slice(&self.label.to_be_bytes()[1..]);
+        self.tunnel_identifier.write(buffer)
+    }
+
+    fn read(buffer: &mut Buffer) -> Result<Self> where Self: Sized {
+        let flags = PMSITunnelFlags::from_bits_retain(u8::read(buffer)?);
+        let tunnel_type = u8::read(buffer)?;
+        let [a, b, c] = buffer.read_bytes_array::<3>()?;
+        Ok(Self {
+            flags,
+            label: u32::from_be_bytes([0, a, b, c]),
+            tunnel_identifier: PMSITunnelIdentifier::read_with(buffer, tunnel_type)?
+        })
+    }
+}
+
+bitflags! {
+    #[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+    pub struct PMSITunnelFlags: u8 {
+        /// The receivers of the route are requested to respond with a Leaf A-D route.
+        const LEAF_INFORMATION_REQUIRED = 0b00000001;
+        const NONE                      = 0b00000000;
+    }
+}
+
+/// This is the representation of the tunnel type and the tunnel identifier of the PMSI Tunnel
+/// attribute. The tunnel identifier is decoded for the tunnel types of
+/// [RFC6514, Section 5](https://www.rfc-editor.org/rfc/rfc6514#section-5) and
+/// [RFC7524](https://www.rfc-editor.org/rfc/rfc7524).
+#[derive(Clone, PartialEq, Eq, Debug, Hash)]
+pub enum PMSITunnelIdentifier {
+    /// The route doesn't contain information about a tunnel (Type 0).
+    NoTunnelInformation,
+    /// This identifier contains the Extended Tunnel ID, the Tunnel ID and the P2MP ID of the
+    /// SESSION object of the RSVP-TE P2MP LSP (Type 1).
+    RSVPTEP2MPLSP(IpAddr, u16, u32),
+    /// This identifier contains the root node address and the opaque value of the P2MP FEC
+    /// element of the mLDP P2MP LSP, as defined in [RFC6388](https://www.rfc-editor.org/rfc/rfc6388)
+    /// (Type 2).
+    MLDPP2MPLSP(IpAddr, Vec<u8>),
+    /// This identifier contains the sender address and the multicast group of the tree (Type 3).
+    PIMSSMTree(IpAddr, IpAddr),
+    /// This identifier contains the sender address and the multicast group of the tree (Type 4).
+    PIMSMTree(IpAddr, IpAddr),
+    /// This identifier contains the sender address and the multicast group of the tree (Type 5).
+    BIDIRPIMTree(IpAddr, IpAddr),
+    /// This identifier contains the unicast address of the tunnel endpoint, to which the traffic
+    /// is replicated (Type 6).
+    IngressReplication(IpAddr),
+    /// This identifier contains the root node address and the opaque value of the MP2MP FEC
+    /// element of the mLDP MP2MP LSP (Type 7).
+    MLDPMP2MPLSP(IpAddr, Vec<u8>),
+    Unknown(u8, Vec<u8>)
+}
+
+impl PMSITunnelIdentifier {
+
+    pub fn tunnel_type(&self) -> u8 {
+        match self {
+            Self::NoTunnelInformation => 0,
+            Self::RSVPTEP2MPLSP(..) => 1,
+            Self::MLDPP2MPLSP(..) => 2,
+            Self::PIMSSMTree(..) => 3,
+            Self::PIMSMTree(..) => 4,
+            Self::BIDIRPIMTree(..) => 5,
+            Self::IngressReplication(_) => 6,
+            Self::MLDPMP2MPLSP(..) => 7,
+            Self::Unknown(tunnel_type, _) => *tunnel_type
+        }
+    }
+
+    fn write(&self, buffer: &mut Buffer) -> Result<()> {
+        match self {
+            Self::NoTunnelInformation => {},
+            Self::RSVPTEP2MPLSP(extended_tunnel_id, tunnel_id, p2mp_id) => {
+                write_address(buffer, extended_tunnel_id)?;
+                0u16.write(buffer)?;
+                tunnel_id.write(buffer)?;
+                p2mp_id.write(buffer)?;
+            },
+            Self::MLDPP2MPLSP(root, opaque) | Self::MLDPMP2MPLSP(root, opaque) => {
+                if opaque.len() > u16::MAX as usize {
+                    return Err(ErrorType::WriteError.err(format!("Unable to write mLDP FEC element! The opaque value is {} bytes long", opaque.len())));
+                }
+                let fec_type: u8 = if matches!(self, Self::MLDPP2MPLSP(..)) { 6 } else { 8 };
+                fec_type.write(buffer)?;
+                match root {
+                    IpAddr::V4(_) => buffer.write_bytes_array([0, 1, 4]),
+                    IpAddr::V6(_) => buffer.write_bytes_array([0, 2, 16])
+                }
+                write_address(buffer, root)?;
+                (opaque.len() as u16).write(buffer)?;
+                buffer.write_bytes_vector(opaque);
+            },
+            Self::PIMSSMTree(sender, group) | Self::PIMSMTree(sender, group) | Self::BIDIRPIMTree(sender, group) => {
+                write_address(buffer, sender)?;
+                write_address(buffer, group)?;
+            },
+            Self::IngressReplication(endpoint) => write_address(buffer, endpoint)?,
+            Self::Unknown(_, data) => buffer.write_bytes_vector(data)
+        }
+        Ok(())
+    }
+
+    /// This function reads the tunnel identifier of the tunnel type from the remaining bytes of
+    /// the buffer. The address family of the addresses is detected by the length of the identifier.
+    fn read_with(buffer: &mut Buffer, tunnel_type: u8) -> Result<Self> {
+        let length = buffer.remaining();
+        let identifier = match (tunnel_type, length) {
+            (0, 0) => Self::NoTunnelInformation,
+            (1, 12 | 24) => {
+                let extended_tunnel_id = read_address(buffer, length - 8)?;
+                u16::read(buffer)?;
+                Self::RSVPTEP2MPLSP(extended_tunnel_id, u16::read(buffer)?, u32::read(buffer)?)
+            },
+            (2 | 7, _) => {
+                let fec_type = u8::read(buffer)?;
+                let expected = if tunnel_type == 2 { 6 } else { 8 };
+                if fec_type != expected {
+                    return Err(malformed(&format!("Expected mLDP FEC element of type {expected}, but got type {fec_type}")));
+                }
+
+                let address_family = u16::read(buffer)?;
+                let address_length = u8::read(buffer)? as usize;
+                if !matches!((address_family, address_length), (1, 4) | (2, 16)) {
+                    return Err(malformed(&format!("Unexpected root node address of family {address_family} with {address_length} bytes")));
+                }
+                let root = read_address(buffer, address_length)?;
+                let opaque_length = u16::read(buffer)? as usize;
+                let opaque = buffer.read_bytes_vector(opaque_length)?;
+                if tunnel_type == 2 { Self::MLDPP2MPLSP(root, opaque) } else { Self::MLDPMP2MPLSP(root, opaque) }
+            },
+            (3..=5, 8 | 32) => {
+                let sender = read_address(buffer, length / 2)?;
+                let group = read_address(buffer, length / 2)?;
+                match tunnel_type {
+                    3 => Self::PIMSSMTree(sender, group),
+                    4 => Self::PIMSMTree(sender, group),
+                    _ => Self::BIDIRPIMTree(sender, group)
+                }
+            },
+            (6, 4 | 16) => Self::IngressReplication(read_address(buffer, length)?),
+            (0..=7, _) => return Err(malformed(&format!("Unexpected length {length} of tunnel identifier of tunnel type {tunnel_type}"))),
+            _ => Self::Unknown(tunnel_type, buffer.read_bytes_vector(length)?)
+        };
+        Ok(identifier)
+    }
+
+}
+
+fn write_address(buffer: &mut Buffer, address: &IpAddr) -> Result<()> {
+    match address {
+        IpAddr::V4(address) => address.write(buffer),
+        IpAddr::V6(address) => address.write(buffer)
+    }
+}
+
+fn read_address(buffer: &mut Buffer, length: usize) -> Result<IpAddr> {
+    Ok(match length {
+        4 => IpAddr::V4(Ipv4Addr::read(buffer)?),
+        _ => IpAddr::V6(Ipv6Addr::read(buffer)?)
+    })
+}
+
+fn malformed(message: &str) -> Error {
+    ErrorType::BGPError(BGPError::update(UpdateMessageError::OptionalAttributeError))
+        .err(format!("Malformed PMSI Tunnel attribute! {message}"))
+}
@@ -0,0 +1,282 @@
+use crate::bgp::error::{BGPError, UpdateMessageError};
+use crate::error::{Error, ErrorType};
+use crate::if_no_std;
+use crate::io::{Buffer, ByteOrder, WriteRead};
+use crate::Result;
+use core::net::Ipv6Addr;
+
+if_no_std! {
+    use alloc::{format, vec::Vec};
+}
+
+/// This is the representation of a TLV of the BGP Prefix-SID attribute, as defined in
+/// [RFC8669, Section 3](https://www.rfc-editor.org/rfc/rfc8669#section-3). The SRv6 Service TLVs
+/// are defined in [RFC9252, Section 2](https://www.rfc-editor.org/rfc/rfc9252#section-2). The
+/// reserved fields of the TLVs are written as zero and ignored on read.
+#[derive(Clone, PartialEq, Eq, Debug, Hash)]
+pub enum PrefixSIDTlv {
+    /// This TLV contains the flags and the label index of the prefix for SR-MPLS (Type 1).
+    LabelIndex(u16, u32),
+    /// This TLV contains the flags and the SRGB ranges of the originator of the prefix (Type 3).
+    OriginatorSRGB(u16, Vec<SRGBRange>),
+    /// This TLV contains the SRv6 SIDs of Layer 3 services like VPNs (Type 5).
+    SRv6L3Service(Vec<SRv6ServiceSubTlv>),
+    /// This TLV contains the SRv6 SIDs of Layer 2 services like EVPN (Type 6).
+    SRv6L2Service(Vec<SRv6ServiceSubTlv>),
+    Unknown(u8, Vec<u8>)
+}
+
+impl WriteRead for PrefixSIDTlv {
+    fn write(&self, buffer: &mut Buffer) -> Result<()> {
+        let value = &mut Buffer::empty(ByteOrder::BigEndian);
+        let ty = match self {
+            Self::LabelIndex(flags, label_index) => {
+                0u8.write(value)?;
+                flags.write(value)?;
+                label_index.write(value)?;
+                1
+            },
+            Self::OriginatorSRGB(flags, ranges) => {
+                flags.write(value)?;
+                for range in ranges {
+                    range.write(value)?;
+                }
+                3
+            },
+            Self::SRv6L3Service(sub_tlvs) | Self::SRv6L2Service(sub_tlvs) => {
+                0u8.write(value)?;
+                for sub_tlv in sub_tlvs {
+                    sub_tlv.write(value)?;
+                }
+                if matches!(self, Self::SRv6L3Service(_)) { 5 } else { 6 }
+            },
+            Self::Unknown(ty, data) => {
+                value.write_bytes_vector(data);
+                *ty
+            }
+        };
+        write_tlv(buffer, ty, value)
+    }
+
+    fn read(buffer: &mut Buffer) -> Result<Self> where Self: Sized {
+        let (ty, value) = &mut read_tlv(buffer)?;
+        let tlv = match ty {
+            1 => {
+                if value.len() != 7 {
+                    return Err(malformed(&format!("Expected Label-Index TLV of 7 bytes, but got {} bytes", value.len())));
+                }
+                u8::read(value)?;
+                Self::LabelIndex(u16::read(value)?, u32::read(value)?)
+            },
+            3 => {
+                if value.len() < 2 || (value.len() - 2) % 6 != 0 {
+                    return Err(malformed(&format!("Unexpected length {} of Originator SRGB TLV", value.len())));
+                }
+                let flags = u16::read(value)?;
+                let mut ranges = Vec::new();
+                while value.remaining() > 0 {
+                    ranges.push(SRGBRange::read(value)?);
+                }
+                Self::OriginatorSRGB(flags, ranges)
+            },
+            5 | 6 => {
+                u8::read(value)?;
+                let mut sub_tlvs = Vec::new();
+                while value.remaining() > 0 {
+                    sub_tlvs.push(SRv6ServiceSubTlv::read(value)?);
+                }
+                if *ty == 5 { Self::SRv6L3Service(sub_tlvs) } else { Self::SRv6L2Service(sub_tlvs) }
+            },
+            _ => Self::Unknown(*ty, value.read_bytes_vector(value.len())?)
+        };
+        Ok(tlv)
+    }
+}
+
+/// This is the representation of a range of the Segment Routing Global Block (SRGB) with the first
+/// label and the number of labels in the range. Both values are encoded as 3-byte values.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub struct SRGBRange {
+    pub base: u32,
+    pub range: u32
+}
+
+impl WriteRead for SRGBRange {
+    fn write(&self, buffer: &mut Buffer) -> Result<()> {
+        if self.base > 0xFFFFFF || self.range > 0xFFFFFF {
+            return Err(ErrorType::WriteError.err(format!("Unable to write SRGB range {self:?}! The base and the range are limited to 3 bytes")));
+        }
+        buffer.write_bytes_slice(&self.base.to_be_bytes()[1..]);
+        buffer.write_bytes_slice(&self.range.to_be_bytes()[1..]);
+        Ok(())
+    }
+
+    fn read(buffer: &mut Buffer) -> Result<Self> where Self: Sized {
+        let [a, b, c] = buffer.read_bytes_array::<3>()?;
+        let base = u32::from_be_bytes([0, a, b, c]);
+        let [a, b, c] = buffer.read_bytes_array::<3>()?;
+        Ok(Self {
+            base,
+            range: u32::from_be_bytes([0, a, b, c])
+        })
+    }
+}
+
+/// This is the representation of a sub-TLV of the SRv6 Service TLVs, as defined in
+/// [RFC9252, Section 3](https://www.rfc-editor.org/rfc/rfc9252#section-3).
+#[derive(Clone, PartialEq, Eq, Debug, Hash)]
+pub enum SRv6ServiceSubTlv {
+    /// This sub-TLV contains a SRv6 SID of the service (Type 1).
+    SIDInformation(SRv6SIDInformation),
+    Unknown(u8, Vec<u8>)
+}
+
+impl WriteRead for SRv6ServiceSubTlv {
+    fn write(&self, buffer: &mut Buffer) -> Result<()> {
+        let value = &mut Buffer::empty(ByteOrder::BigEndian);
+        let ty = match self {
+            Self::SIDInformation(information) => {
+                0u8.write(value)?;
+                information.sid.write(value)?;
+                information.flags.write(value)?;
+                information.endpoint_behavior.write(value)?;
+                0u8.write(value)?;
+                for sub_sub_tlv in &information.sub_sub_tlvs {
+                    sub_sub_tlv.write(value)?;
+                }
+                1
+            },
+            Self::Unknown(ty, data) => {
+                value.write_bytes_vector(data);
+                *ty
+            }
+        };
+        write_tlv(buffer, ty, value)
+    }
+
+    fn read(buffer: &mut Buffer) -> Result<Self> where Self: Sized {
+        let (ty, value) = &mut read_tlv(buffer)?;
+        if *ty != 1 {
+            return Ok(Self::Unknown(*ty, value.read_bytes_vector(value.len())?));
+        }
+
+        if value.len() < 21 {
+            return Err(malformed(&format!("Expected SRv6 SID Information Sub-TLV of at least 21 bytes, but got {} bytes", value.len())));
+        }
+        u8::read(value)?;
+        let sid = Ipv6Addr::read(value)?;
+        let flags = u8::read(value)?;
+        let endpoint_behavior = u16::read(value)?;
+        u8::read(value)?;
+
+        let mut sub_sub_tlvs = Vec::new();
+        while value.remaining() > 0 {
+            sub_sub_tlvs.push(SRv6ServiceDataSubSubTlv::read(value)?);
+        }
+        Ok(Self::SIDInformation(SRv6SIDInformation {
+            sid,
+            flags,
+            endpoint_behavior,
+            sub_sub_tlvs
+        }))
+    }
+}
+
+/// This is the representation of the SRv6 SID Information Sub-TLV, as defined in
+/// [RFC9252, Section 3.1](https://www.rfc-editor.org/rfc/rfc9252#section-3.1). The endpoint
+/// behavior is a code point of the "SRv6 Endpoint Behaviors" registry of
+/// [RFC8986](https://www.rfc-editor.org/rfc/rfc8986).
+#[derive(Clone, PartialEq, Eq, Debug, Hash)]
+pub struct SRv6SIDInformation {
+    pub sid: Ipv6Addr,
+    pub flags: u8,
+    pub endpoint_behavior: u16,
+    pub sub_sub_tlvs: Vec<SRv6ServiceDataSubSubTlv>
+}
+
+/// This is the representation of a sub-sub-TLV of the SRv6 SID Information Sub-TLV, as defined in
+/// [RFC9252, Section 3.2](https://www.rfc-editor.org/rfc/rfc9252#section-3.2).
+#[derive(Clone, PartialEq, Eq, Debug, Hash)]
+pub enum SRv6ServiceDataSubSubTlv {
+    /// This sub-sub-TLV describes the structure of the SID and the part of the SID, which is
+    /// transposed into the label field of the NLRI (Type 1).
+    SIDStructure(SRv6SIDStructure),
+    Unknown(u8, Vec<u8>)
+}
+
+impl WriteRead for SRv6ServiceDataSubSubTlv {
+    fn write(&self, buffer: &mut Buffer) -> Result<()> {
+        let value = &mut Buffer::empty(ByteOrder::BigEndian);
+        let ty = match self {
+            Self::SIDStructure(structure) => {
+                value.write_bytes_array([
+                    structure.locator_block_length,
+                    structure.locator_node_length,
+                    structure.function_length,
+                    structure.argument_length,
+                    structure.transposition_length,
+                    structure.transposition_offset
+                ]);
+                1
+            },
+            Self::Unknown(ty, data) => {
+                value.write_bytes_vector(data);
+                *ty
+            }
+        };
+        write_tlv(buffer, ty, value)
+    }
+
+    fn read(buffer: &mut Buffer) -> Result<Self> where Self: Sized {
+        let (ty, value) = &mut read_tlv(buffer)?;
+        if *ty != 1 {
+            return Ok(Self::Unknown(*ty, value.read_bytes_vector(value.len())?));
+        }
+
+        if value.len() != 6 {
+            return Err(malformed(&format!("Expected SRv6 SID Structure Sub-Sub-TLV of 6 bytes, but got {} bytes", value.len())));
+        }
+        let [locator_block_length, locator_node_length, function_length, argument_length, transposition_length, transposition_offset] = value.read_bytes_array::<6>()?;
+        Ok(Self::SIDStructure(SRv6SIDStructure {
+            locator_block_length,
+            locator_node_length,
+            function_length,
+            argument_length,
+            transposition_length,
+            transposition_offset
+        }))
+    }
+}
+
+/// This is the representation of the SRv6 SID Structure Sub-Sub-TLV, as defined in
+/// [RFC9252, Section 3.2.1](https://www.rfc-editor.org/rfc/rfc9252#section-3.2.1). All lengths
+/// and the offset are in bits.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub struct SRv6SIDStructure {
+    pub locator_block_length: u8,
+    pub locator_node_length: u8,
+    pub function_length: u8,
+    pub argument_length: u8,
+    pub transposition_length: u8,
+    pub transposition_offset: u8
+}
+
+fn write_tlv(buffer: &mut Buffer, ty: u8, value: &Buffer) -> Result<()> {
+    if value.len() > u16::MAX as usize {
+        return Err(ErrorType::WriteError.err(format!("Unable to write Prefix-SID TLV {ty}! The value is {} bytes long", value.len())));
+    }
+    ty.write(buffer)?;
+    (value.len() as u16).write(buffer)?;
+    value.write_buffer(buffer)
+}
+
+fn read_tlv(buffer: &mut Buffer) -> Result<(u8, Buffer)> {
+    let ty = u8::read(buffer)?;
+    let length = u16::read(buffer)? as usize;
+    Ok((ty, Buffer::read_buffer(buffer, length)?))
+}
+
+fn malformed(message: &str) -> Error {
+    ErrorType::BGPError(BGPError::update(UpdateMessageError::OptionalAttributeError))
+        .err(format!("Malformed Prefix-SID attribute! {message}"))
+}
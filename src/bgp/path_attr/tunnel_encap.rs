@@ -0,0 +1,396 @@
+use crate::bgp::error::{BGPError, UpdateMessageError};
+use crate::error::{Error, ErrorType};
+use crate::if_no_std;
+use crate::io::{Buffer, ByteOrder, WriteRead};
+use crate::Result;
+use core::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+if_no_std! {
+    use alloc::{format, vec::Vec};
+}
+
+/// This is the representation of a Tunnel TLV of the Tunnel Encapsulation attribute, as defined in
+/// [RFC9012, Section 2](https://www.rfc-editor.org/rfc/rfc9012#section-2). The TLV describes a
+/// tunnel of the tunnel type with the sub-TLVs as properties of the tunnel.
+#[derive(Clone, PartialEq, Eq, Debug, Hash)]
+pub struct TunnelTlv {
+    pub tunnel_type: TunnelType,
+    pub sub_tlvs: Vec<TunnelSubTlv>
+}
+
+impl WriteRead for TunnelTlv {
+    fn write(&self, buffer: &mut Buffer) -> Result<()> {
+        let value = &mut Buffer::empty(ByteOrder::BigEndian);
+        for sub_tlv in &self.sub_tlvs {
+            sub_tlv.write(value)?;
+        }
+
+        if value.len() > u16::MAX as usize {
+            return Err(ErrorType::WriteError.err(format!("Unable to write Tunnel TLV {:?}! The value is {} bytes long", self.tunnel_type, value.len())));
+        }
+        u16::from(self.tunnel_type).write(buffer)?;
+        (value.len() as u16).write(buffer)?;
+        value.write_buffer(buffer)
+    }
+
+    fn read(buffer: &mut Buffer) -> Result<Self> where Self: Sized {
+        let tunnel_type = TunnelType::from(u16::read(buffer)?);
+        let length = u16::read(buffer)? as usize;
+        let value = &mut Buffer::read_buffer(buffer, length)?;
+
+        let mut sub_tlvs = Vec::new();
+        while value.remaining() > 0 {
+            sub_tlvs.push(TunnelSubTlv::read_with(value, tunnel_type)?);
+        }
+        Ok(Self {
+            tunnel_type,
+            sub_tlvs
+        })
+    }
+}
+
+/// This is the representation of the tunnel types of the "BGP Tunnel Encapsulation Attribute
+/// Tunnel Types" registry, which are supported by this library.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub enum TunnelType {
+    GRE,
+    VXLAN,
+    NVGRE,
+    MPLS,
+    MPLSInGRE,
+    VXLANGPE,
+    MPLSInUDP,
+    /// This tunnel type describes a Segment Routing Policy, as defined in
+    /// [RFC9830](https://www.rfc-editor.org/rfc/rfc9830).
+    SRPolicy,
+    Unknown(u16)
+}
+
+impl From<u16> for TunnelType {
+    fn from(value: u16) -> Self {
+        match value {
+            2 => Self::GRE,
+            8 => Self::VXLAN,
+            9 => Self::NVGRE,
+            10 => Self::MPLS,
+            11 => Self::MPLSInGRE,
+            12 => Self::VXLANGPE,
+            13 => Self::MPLSInUDP,
+            15 => Self::SRPolicy,
+            _ => Self::Unknown(value)
+        }
+    }
+}
+
+impl From<TunnelType> for u16 {
+    fn from(value: TunnelType) -> Self {
+        match value {
+            TunnelType::GRE => 2,
+            TunnelType::VXLAN => 8,
+            TunnelType::NVGRE => 9,
+            TunnelType::MPLS => 10,
+            TunnelType::MPLSInGRE => 11,
+            TunnelType::VXLANGPE => 12,
+            TunnelType::MPLSInUDP => 13,
+            TunnelType::SRPolicy => 15,
+            TunnelType::Unknown(value) => value
+        }
+    }
+}
+
+/// This is the representation of a sub-TLV of a Tunnel TLV, as defined in
+/// [RFC9012, Section 3](https://www.rfc-editor.org/rfc/rfc9012#section-3). Sub-TLVs with a type
+/// of 128 or higher have a 2-byte length, all other sub-TLVs have a 1-byte length.
+#[derive(Clone, PartialEq, Eq, Debug, Hash)]
+pub enum TunnelSubTlv {
+    /// This sub-TLV contains the tunnel type specific encapsulation information (Type 1).
+    Encapsulation(TunnelEncapsulation),
+    /// This sub-TLV contains the Ethertype of the payload of the tunnel (Type 2).
+    ProtocolType(u16),
+    /// This sub-TLV contains the color of the tunnel, which is encoded as Color Extended
+    /// Community (Type 4).
+    Color(u32),
+    /// This sub-TLV contains the AS and the address of the remote endpoint of the tunnel, which
+    /// is called Tunnel Egress Endpoint in the RFC. The address is omitted, if the AFI is zero
+    /// (Type 6).
+    RemoteEndpoint(u32, Option<IpAddr>),
+    /// This sub-TLV contains the UDP destination port of the tunnel (Type 8).
+    UDPDestinationPort(u16),
+    /// This sub-TLV contains a segment list of a SR Policy, as defined in
+    /// [RFC9830, Section 2.4.4](https://www.rfc-editor.org/rfc/rfc9830#section-2.4.4) (Type 128).
+    SegmentList(Vec<SegmentListSubTlv>),
+    Unknown(u8, Vec<u8>)
+}
+
+impl TunnelSubTlv {
+
+    fn write(&self, buffer: &mut Buffer) -> Result<()> {
+        let value = &mut Buffer::empty(ByteOrder::BigEndian);
+        let ty = match self {
+            Self::Encapsulation(encapsulation) => {
+                encapsulation.write(value)?;
+                1
+            },
+            Self::ProtocolType(protocol_type) => {
+                protocol_type.write(value)?;
+                2
+            },
+            Self::Color(color) => {
+                value.write_bytes_array([0x03, 0x0B, 0, 0]);
+                color.write(value)?;
+                4
+            },
+            Self::RemoteEndpoint(autonomous_system, address) => {
+                autonomous_system.write(value)?;
+                match address {
+                    None => 0u16.write(value)?,
+                    Some(IpAddr::V4(address)) => {
+                        1u16.write(value)?;
+                        address.write(value)?;
+                    },
+                    Some(IpAddr::V6(address)) => {
+                        2u16.write(value)?;
+                        address.write(value)?;
+                    }
+                }
+                6
+            },
+            Self::UDPDestinationPort(port) => {
+                port.write(value)?;
+                8
+            },
+            Self::SegmentList(sub_tlvs) => {
+                0u8.write(value)?;
+                for sub_tlv in sub_tlvs {
+                    sub_tlv.write(value)?;
+                }
+                128
+            },
+            Self::Unknown(ty, data) => {
+                value.write_bytes_vector(data);
+                *ty
+            }
+        };
+
+        ty.write(buffer)?;
+        if ty >= 128 {
+            if value.len() > u16::MAX as usize {
+                return Err(ErrorType::WriteError.err(format!("Unable to write tunnel sub-TLV {ty}! The value is {} bytes long", value.len())));
+            }
+            (value.len() as u16).write(buffer)?;
+        } else {
+            if value.len() > u8::MAX as usize {
+                return Err(ErrorType::WriteError.err(format!("Unable to write tunnel sub-TLV {ty}! The value is {} bytes long", value.len())));
+            }
+            (value.len() as u8).write(buffer)?;
+        }
+        value.write_buffer(buffer)
+    }
+
+    /// This function reads the sub-TLV from the buffer. The value of the Encapsulation sub-TLV
+    /// depends on the type of the tunnel.
+    fn read_with(buffer: &mut Buffer, tunnel_type: TunnelType) -> Result<Self> {
+        let ty = u8::read(buffer)?;
+        let length = if ty >= 128 {
+            u16::read(buffer)? as usize
+        } else {
+            u8::read(buffer)? as usize
+        };
+        let value = &mut Buffer::read_buffer(buffer, length)?;
+
+        let sub_tlv = match ty {
+            1 => Self::Encapsulation(TunnelEncapsulation::read_with(value, tunnel_type)?),
+            2 if length == 2 => Self::ProtocolType(u16::read(value)?),
+            4 if length == 8 => {
+                if value.read_bytes_array::<2>()? != [0x03, 0x0B] {
+                    return Err(malformed("Expected Color Extended Community in Color sub-TLV"));
+                }
+                u16::read(value)?;
+                Self::Color(u32::read(value)?)
+            },
+            6 if length >= 6 => {
+                let autonomous_system = u32::read(value)?;
+                let afi = u16::read(value)?;
+                match (afi, value.remaining()) {
+                    (0, 0) => Self::RemoteEndpoint(autonomous_system, None),
+                    (1, 4) => Self::RemoteEndpoint(autonomous_system, Some(IpAddr::V4(Ipv4Addr::read(value)?))),
+                    (2, 16) => Self::RemoteEndpoint(autonomous_system, Some(IpAddr::V6(Ipv6Addr::read(value)?))),
+                    _ => return Err(malformed(&format!("Unexpected address of AFI {afi} with {} bytes in Tunnel Egress Endpoint sub-TLV", value.remaining())))
+                }
+            },
+            8 if length == 2 => Self::UDPDestinationPort(u16::read(value)?),
+            128 if length >= 1 => {
+                u8::read(value)?;
+                let mut sub_tlvs = Vec::new();
+                while value.remaining() > 0 {
+                    sub_tlvs.push(SegmentListSubTlv::read(value)?);
+                }
+                Self::SegmentList(sub_tlvs)
+            },
+            2 | 4 | 6 | 8 | 128 => return Err(malformed(&format!("Unexpected length {length} of tunnel sub-TLV {ty}"))),
+            _ => Self::Unknown(ty, value.read_bytes_vector(length)?)
+        };
+        Ok(sub_tlv)
+    }
+
+}
+
+/// This is the representation of the value of the Encapsulation sub-TLV, as defined in
+/// [RFC9012, Section 3.2](https://www.rfc-editor.org/rfc/rfc9012#section-3.2). The value is only
+/// decoded for the VXLAN, NVGRE and GRE tunnel types.
+#[derive(Clone, PartialEq, Eq, Debug, Hash)]
+pub enum TunnelEncapsulation {
+    /// This encapsulation contains the optional 3-byte virtual network identifier and the optional
+    /// MAC address of the VXLAN or NVGRE tunnel.
+    VXLAN(Option<u32>, Option<[u8; 6]>),
+    NVGRE(Option<u32>, Option<[u8; 6]>),
+    /// This encapsulation contains the key of the GRE tunnel.
+    GRE(u32),
+    Unknown(Vec<u8>)
+}
+
+impl TunnelEncapsulation {
+
+    fn write(&self, buffer: &mut Buffer) -> Result<()> {
+        match self {
+            Self::VXLAN(network_identifier, mac_address) | Self::NVGRE(network_identifier, mac_address) => {
+                let mut flags = 0u8;
+                if network_identifier.is_some() {
+                    flags |= 0x80;
+                }
+                if mac_address.is_some() {
+                    flags |= 0x40;
+                }
+
+                let network_identifier = network_identifier.unwrap_or_default();
+                if network_identifier > 0xFFFFFF {
+                    return Err(ErrorType::WriteError.err(format!("Unable to write virtual network identifier {network_identifier}! The identifier is limited to 3 bytes")));
+                }
+                flags.write(buffer)?;
+                buffer.write_bytes_slice(&network_identifier.to_be_bytes()[1..]);
+                buffer.write_bytes_array(mac_address.unwrap_or_default());
+                0u16.write(buffer)
+            },
+            Self::GRE(key) => key.write(buffer),
+            Self::Unknown(data) => {
+                buffer.write_bytes_vector(data);
+                Ok(())
+            }
+        }
+    }
+
+    fn read_with(buffer: &mut Buffer, tunnel_type: TunnelType) -> Result<Self> {
+        match tunnel_type {
+            TunnelType::VXLAN | TunnelType::NVGRE => {
+                if buffer.len() != 12 {
+                    return Err(malformed(&format!("Expected Encapsulation sub-TLV of 12 bytes for {tunnel_type:?}, but got {} bytes", buffer.len())));
+                }
+                let flags = u8::read(buffer)?;
+                let [a, b, c] = buffer.read_bytes_array::<3>()?;
+                let network_identifier = (flags & 0x80 != 0).then_some(u32::from_be_bytes([0, a, b, c]));
+                let mac_address = buffer.read_bytes_array::<6>()?;
+                let mac_address = (flags & 0x40 != 0).then_some(mac_address);
+                u16::read(buffer)?;
+
+                if tunnel_type == TunnelType::VXLAN {
+                    Ok(Self::VXLAN(network_identifier, mac_address))
+                } else {
+                    Ok(Self::NVGRE(network_identifier, mac_address))
+                }
+            },
+            TunnelType::GRE if buffer.len() == 4 => Ok(Self::GRE(u32::read(buffer)?)),
+            _ => Ok(Self::Unknown(buffer.read_bytes_vector(buffer.len())?))
+        }
+    }
+
+}
+
+/// This is the representation of a sub-TLV of the Segment List sub-TLV, as defined in
+/// [RFC9830, Section 2.4.4](https://www.rfc-editor.org/rfc/rfc9830#section-2.4.4). The flags
+/// of the segments are preserved as raw byte.
+#[derive(Clone, PartialEq, Eq, Debug, Hash)]
+pub enum SegmentListSubTlv {
+    /// This sub-TLV contains the flags and the weight of the segment list (Type 9).
+    Weight(u8, u32),
+    /// This sub-TLV contains the flags and the MPLS label stack entry of a SR-MPLS segment
+    /// (Segment Type A, Type 1).
+    MPLSSegment(u8, u32),
+    /// This sub-TLV contains the flags, the SRv6 SID and the optional endpoint behavior and SID
+    /// structure of a SRv6 segment (Segment Type B, Type 13).
+    SRv6Segment(u8, Ipv6Addr, Option<[u8; 8]>),
+    Unknown(u8, Vec<u8>)
+}
+
+impl WriteRead for SegmentListSubTlv {
+    fn write(&self, buffer: &mut Buffer) -> Result<()> {
+        let value = &mut Buffer::empty(ByteOrder::BigEndian);
+        let ty = match self {
+            Self::Weight(flags, weight) => {
+                flags.write(value)?;
+                0u8.write(value)?;
+                weight.write(value)?;
+                9
+            },
+            Self::MPLSSegment(flags, label) => {
+                flags.write(value)?;
+                0u8.write(value)?;
+                label.write(value)?;
+                1
+            },
+            Self::SRv6Segment(flags, sid, behavior) => {
+                flags.write(value)?;
+                0u8.write(value)?;
+                sid.write(value)?;
+                if let Some(behavior) = behavior {
+                    value.write_bytes_array(*behavior);
+                }
+                13
+            },
+            Self::Unknown(ty, data) => {
+                value.write_bytes_vector(data);
+                *ty
+            }
+        };
+
+        if value.len() > u8::MAX as usize {
+            return Err(ErrorType::WriteError.err(format!("Unable to write segment list sub-TLV {ty}! The value is {} bytes long", value.len())));
+        }
+        ty.write(buffer)?;
+        (value.len() as u8).write(buffer)?;
+        value.write_buffer(buffer)
+    }
+
+    fn read(buffer: &mut Buffer) -> Result<Self> where Self: Sized {
+        let ty = u8::read(buffer)?;
+        let length = u8::read(buffer)? as usize;
+        let value = &mut Buffer::read_buffer(buffer, length)?;
+
+        let sub_tlv = match (ty, length) {
+            (9, 6) | (1, 6) => {
+                let flags = u8::read(value)?;
+                u8::read(value)?;
+                let value = u32::read(value)?;
+                if ty == 9 { Self::Weight(flags, value) } else { Self::MPLSSegment(flags, value) }
+            },
+            (13, 18) | (13, 26) => {
+                let flags = u8::read(value)?;
+                u8::read(value)?;
+                let sid = Ipv6Addr::read(value)?;
+                let behavior = if value.remaining() > 0 {
+                    Some(value.read_bytes_array::<8>()?)
+                } else {
+                    None
+                };
+                Self::SRv6Segment(flags, sid, behavior)
+            },
+            (1 | 9 | 13, _) => return Err(malformed(&format!("Unexpected length {length} of segment list sub-TLV {ty}"))),
+            _ => Self::Unknown(ty, value.read_bytes_vector(length)?)
+        };
+        Ok(sub_tlv)
+    }
+}
+
+fn malformed(message: &str) -> Error {
+    ErrorType::BGPError(BGPError::update(UpdateMessageError::OptionalAttributeError))
+        .err(format!("Malformed Tunnel Encapsulation attribute! {message}"))
+}
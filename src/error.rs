@@ -9,6 +9,7 @@ if_no_std! {
 pub enum ErrorType {
     ReadError,
     WriteError,
+    ParseError,
     OtherError,
     #[cfg(feature = "log")]
     LogError,
@@ -31,6 +32,16 @@ pub struct Error {
     ty: ErrorType,
 }
 
+impl Error {
+    pub fn ty(&self) -> ErrorType {
+        self.ty
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
 impl Display for Error {
     fn fmt(&self, formatter: &mut Formatter<'_>) -> core::fmt::Result {
         write!(formatter, "{} ({:?})", self.message, self.ty)
@@ -2,6 +2,7 @@ use crate::error::ErrorType;
 use crate::error::ErrorType::ReadError;
 use crate::{if_no_std, if_std};
 use crate::Result;
+use core::net::{Ipv4Addr, Ipv6Addr};
 
 if_no_std! {
     use {
@@ -316,3 +317,25 @@ write_read_number!(u32);
 write_read_number!(i32);
 write_read_number!(u64);
 write_read_number!(i64);
+
+impl WriteRead for Ipv4Addr {
+    fn write(&self, buffer: &mut Buffer) -> Result<()> {
+        buffer.write_bytes_array(self.octets());
+        Ok(())
+    }
+
+    fn read(buffer: &mut Buffer) -> Result<Self> {
+        Ok(Ipv4Addr::from(buffer.read_bytes_array::<4>()?))
+    }
+}
+
+impl WriteRead for Ipv6Addr {
+    fn write(&self, buffer: &mut Buffer) -> Result<()> {
+        buffer.write_bytes_array(self.octets());
+        Ok(())
+    }
+
+    fn read(buffer: &mut Buffer) -> Result<Self> {
+        Ok(Ipv6Addr::from(buffer.read_bytes_array::<16>()?))
+    }
+}
@@ -0,0 +1,69 @@
+use core::net::Ipv4Addr;
+use crate::bgp::intern::AttributeInterner;
+use crate::bgp::path_attr::{ASPath, ASPathSegment, Attribute, AttributeFlags, AttributeType, AttributeValue, Origin};
+use crate::bgp::{Packet, RoutePrefix, SessionParameters};
+use crate::if_no_std;
+use crate::io::{Buffer, ByteOrder, WriteRead};
+
+if_no_std! {
+    use alloc::{vec, vec::Vec};
+}
+
+fn attributes(local_pref: u32) -> Vec<Attribute> {
+    vec![
+        Attribute::new(AttributeType::Origin, AttributeFlags::TRANSITIVE, AttributeValue::Origin(Origin::IGP)),
+        Attribute::new(AttributeType::ASPath, AttributeFlags::TRANSITIVE, AttributeValue::ASPath(ASPath::new(vec![ASPathSegment::ASSequence(vec![64600])]))),
+        Attribute::new(AttributeType::NextHop, AttributeFlags::TRANSITIVE, AttributeValue::NextHop(vec![192, 0, 2, 1])),
+        Attribute::new(AttributeType::LocalPref, AttributeFlags::TRANSITIVE, AttributeValue::LocalPref(local_pref))
+    ]
+}
+
+#[test]
+fn test_attribute_interner() {
+    let interner = &mut AttributeInterner::new();
+    let first = interner.intern(attributes(100));
+    let mut reversed = attributes(100);
+    reversed.reverse();
+    let second = interner.intern(reversed);
+    let third = interner.intern(attributes(200));
+
+    assert_eq!(first, second);
+    assert_ne!(first, third);
+    assert_eq!(&*first, &attributes(100)[..]);
+    assert_eq!(first.references(), 2);
+    assert_eq!(interner.len(), 2);
+
+    // Lists are dropped with the last handle, without shrinking the interner
+    drop(third);
+    assert_eq!(interner.len(), 1);
+    let third = interner.intern(attributes(200));
+    assert_eq!(third.references(), 1);
+    assert_eq!(interner.len(), 2);
+
+    drop((first, second, third));
+    assert!(interner.is_empty());
+    interner.shrink();
+    assert!(interner.is_empty());
+}
+
+#[test]
+fn test_interned_update() {
+    let interner = &mut AttributeInterner::new();
+    let mut handles = Vec::new();
+    for prefix in [1, 2, 3] {
+        let buffer = &mut Buffer::empty(ByteOrder::BigEndian);
+        Packet::Update(vec![], vec![RoutePrefix::IPv4(Ipv4Addr::new(prefix, 0, 0, 0), 8).into()], attributes(100)).write(buffer).unwrap();
+        buffer.reset_position();
+        let update = interner.read_update(buffer, &SessionParameters::default()).unwrap();
+        assert!(update.withdrawn_routes.is_empty());
+        assert_eq!(update.nlri, vec![RoutePrefix::IPv4(Ipv4Addr::new(prefix, 0, 0, 0), 8).into()]);
+        handles.push(update.attributes);
+    }
+    assert_eq!(interner.len(), 1);
+    assert_eq!(handles[0].references(), 3);
+
+    let buffer = &mut Buffer::empty(ByteOrder::BigEndian);
+    Packet::KeepAlive.write(buffer).unwrap();
+    buffer.reset_position();
+    assert!(interner.read_update(buffer, &SessionParameters::default()).is_err());
+}
@@ -1,10 +1,14 @@
-use crate::bgp::error::ErrorCode;
-use crate::bgp::opt_params::{Capability, OptionalParameter};
-use crate::bgp::{BGPHeader, Packet, RoutePrefix};
+use core::net::Ipv4Addr;
+use crate::bgp::error::{BGPError, ErrorAction, ErrorCode, OpenMessageError, UpdateMessageError};
+use crate::bgp::opt_params::{AddPathMode, Capability, OptionalParameter, Role, AFI, SAFI};
+use crate::bgp::{BGPHeader, Packet, PathPrefix, RoutePrefix, SessionParameters};
 use crate::io::{Buffer, ByteOrder, WriteRead};
+use crate::error::ErrorType;
 use crate::{buffer_test, if_no_std};
-use crate::bgp::path_attr::{Attribute, AttributeFlags, AttributeType, AttributeValue, Community, Origin};
+use crate::bgp::path_attr::{ASPath, ASPathSegment, Attribute, AttributeFlags, AttributeType, AttributeValue, Community, Origin};
 
+pub mod intern;
+pub mod path_attr;
 pub mod prefix;
 
 if_no_std! {
@@ -48,15 +52,18 @@ fn test_notification_packet() {
 fn test_update_packet() {
     let buffer = &mut Buffer::empty(ByteOrder::BigEndian);
     let packet = Packet::Update(vec![
-        RoutePrefix::IPv4(16, vec![255, 255])
+        RoutePrefix::IPv4(Ipv4Addr::new(255, 255, 0, 0), 16).into()
     ], vec![
-        RoutePrefix::IPv4(8, vec![255])
+        RoutePrefix::IPv4(Ipv4Addr::new(255, 0, 0, 0), 8).into()
     ], vec![
-        Attribute::new(AttributeType::Origin, AttributeFlags::NONE, AttributeValue::Origin(Origin::IGP)),
-        Attribute::new(AttributeType::NextHop, AttributeFlags::OPTIONAL, AttributeValue::NextHop(vec![127, 168, 0, 1])),
-        Attribute::new(AttributeType::Community, AttributeFlags::OPTIONAL, AttributeValue::Communities(vec![
-            Community::new(127127127, 1),
-            Community::new(127127128, 2)
+        Attribute::new(AttributeType::Origin, AttributeFlags::TRANSITIVE, AttributeValue::Origin(Origin::IGP)),
+        Attribute::new(AttributeType::ASPath, AttributeFlags::TRANSITIVE, AttributeValue::ASPath(ASPath::new(vec![
+            ASPathSegment::ASSequence(vec![64600])
+        ]))),
+        Attribute::new(AttributeType::NextHop, AttributeFlags::TRANSITIVE, AttributeValue::NextHop(vec![127, 168, 0, 1])),
+        Attribute::new(AttributeType::Community, AttributeFlags::OPTIONAL | AttributeFlags::TRANSITIVE, AttributeValue::Communities(vec![
+            Community::new(64600, 1),
+            Community::NO_EXPORT
         ]))
     ]);
     packet.write(buffer).unwrap();
@@ -79,12 +86,249 @@ fn test_keep_alive_packet() {
 #[test]
 fn test_multiple_packets() {
     let packets = vec![
-        Packet::Update(vec![RoutePrefix::IPv4(15, vec![255, 255])], Vec::new(), Vec::new()),
-        Packet::Update(vec![RoutePrefix::IPv4(8, vec![255])], Vec::new(), Vec::new()),
+        Packet::Update(vec![RoutePrefix::IPv4(Ipv4Addr::new(255, 255, 0, 0), 15).into()], Vec::new(), Vec::new()),
+        Packet::Update(vec![RoutePrefix::IPv4(Ipv4Addr::new(255, 0, 0, 0), 8).into()], Vec::new(), Vec::new()),
     ];
     let buffer = &mut Buffer::system_order();
     Packet::send("buffer", buffer, packets.clone()).unwrap();
     buffer.reset_position();
     let packets_recv = Packet::receive("buffer", buffer).unwrap().unwrap();
     assert_eq!(packets, packets_recv);
-}
\ No newline at end of file
+}
+
+fn update_bytes(withdrawn_routes: &[u8], attributes: &[u8], nlri: &[u8]) -> Buffer {
+    let mut bytes = vec![0xFF; 16];
+    bytes.extend_from_slice(&((23 + withdrawn_routes.len() + attributes.len() + nlri.len()) as u16).to_be_bytes());
+    bytes.push(2);
+    bytes.extend_from_slice(&(withdrawn_routes.len() as u16).to_be_bytes());
+    bytes.extend_from_slice(withdrawn_routes);
+    bytes.extend_from_slice(&(attributes.len() as u16).to_be_bytes());
+    bytes.extend_from_slice(attributes);
+    bytes.extend_from_slice(nlri);
+    Buffer::from_vec(bytes, ByteOrder::BigEndian)
+}
+
+#[test]
+fn test_revised_error_handling() {
+    let parameters = SessionParameters::default();
+    let origin = [0x40, 1, 1, 0];
+    let mandatory = [0x40, 1, 1, 0, 0x40, 2, 0, 0x40, 3, 4, 10, 0, 0, 1];
+    let atomic_aggregate = [0x40, 6, 1, 0];
+    let community = [0xC0, 8, 3, 0, 0, 0];
+
+    // Attribute Discard
+    let bytes = [&mandatory[..], &atomic_aggregate[..]].concat();
+    let (packet, diagnostics) = Packet::read_revised(&mut update_bytes(&[], &bytes, &[8, 10]), &parameters).unwrap();
+    let Packet::Update(_, nlri, attributes) = packet else { panic!("Unexpected packet!") };
+    assert_eq!(nlri, vec![RoutePrefix::IPv4(Ipv4Addr::new(10, 0, 0, 0), 8).into()]);
+    assert_eq!(attributes.len(), 3);
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].type_code, 6);
+    assert_eq!(diagnostics[0].action, ErrorAction::AttributeDiscard);
+    assert_eq!(diagnostics[0].error.ty(), ErrorType::BGPError(BGPError::update(UpdateMessageError::AttributeLengthError)));
+    assert!(Packet::read(&mut update_bytes(&[], &bytes, &[8, 10])).is_err());
+
+    // Treat-as-withdraw
+    let bytes = [&origin[..], &community[..]].concat();
+    let (packet, diagnostics) = Packet::read_revised(&mut update_bytes(&[16, 10, 1], &bytes, &[8, 10]), &parameters).unwrap();
+    assert_eq!(packet, Packet::Update(vec![RoutePrefix::IPv4(Ipv4Addr::new(10, 1, 0, 0), 16).into(), RoutePrefix::IPv4(Ipv4Addr::new(10, 0, 0, 0), 8).into()], Vec::new(), Vec::new()));
+    assert_eq!(diagnostics[0].action, ErrorAction::TreatAsWithdraw);
+
+    // Duplicate attributes are discarded
+    let bytes = [&mandatory[..], &[0x40, 1, 1, 2][..]].concat();
+    let (packet, diagnostics) = Packet::read_revised(&mut update_bytes(&[], &bytes, &[8, 10]), &parameters).unwrap();
+    let Packet::Update(_, _, attributes) = packet else { panic!("Unexpected packet!") };
+    assert_eq!(attributes[0], Attribute::new(AttributeType::Origin, AttributeFlags::TRANSITIVE, AttributeValue::Origin(Origin::IGP)));
+    assert_eq!(diagnostics[0].error.ty(), ErrorType::BGPError(BGPError::update(UpdateMessageError::MalformedAttributeList)));
+
+    // The repeat of a malformed attribute is a duplicate too
+    let bytes = [&[0x40, 1, 2, 0, 0][..], &mandatory[..]].concat();
+    let (_, diagnostics) = Packet::read_revised(&mut update_bytes(&[], &bytes, &[8, 10]), &parameters).unwrap();
+    assert_eq!(diagnostics[0].error.ty(), ErrorType::BGPError(BGPError::update(UpdateMessageError::AttributeLengthError)));
+    assert_eq!(diagnostics[1].type_code, 1);
+    assert_eq!(diagnostics[1].error.ty(), ErrorType::BGPError(BGPError::update(UpdateMessageError::MalformedAttributeList)));
+
+    // Errors of the value keep the subcode of the attribute
+    let bytes = [&mandatory[..4], &[0x40, 2, 4, 5, 1, 0, 1][..], &mandatory[7..]].concat();
+    let (_, diagnostics) = Packet::read_revised(&mut update_bytes(&[], &bytes, &[8, 10]), &parameters).unwrap();
+    assert_eq!(diagnostics[0].type_code, 2);
+    assert_eq!(diagnostics[0].error.ty(), ErrorType::BGPError(BGPError::update(UpdateMessageError::MalformedASPath)));
+
+    // A undefined value of the ORIGIN attribute is handled with Treat-as-withdraw
+    let bytes = [&[0x40, 1, 1, 3][..], &mandatory[4..]].concat();
+    let (packet, diagnostics) = Packet::read_revised(&mut update_bytes(&[], &bytes, &[8, 10]), &parameters).unwrap();
+    assert_eq!(packet, Packet::Update(vec![RoutePrefix::IPv4(Ipv4Addr::new(10, 0, 0, 0), 8).into()], Vec::new(), Vec::new()));
+    assert_eq!(diagnostics[0].action, ErrorAction::TreatAsWithdraw);
+    assert_eq!(diagnostics[0].error.ty(), ErrorType::BGPError(BGPError::update(UpdateMessageError::InvalidOriginAttribute)));
+    assert_eq!(Packet::read(&mut update_bytes(&[], &bytes, &[8, 10])).unwrap_err().ty(), ErrorType::BGPError(BGPError::update(UpdateMessageError::InvalidOriginAttribute)));
+
+    // Missing well-known attributes are handled with Treat-as-withdraw
+    let (packet, diagnostics) = Packet::read_revised(&mut update_bytes(&[], &origin, &[8, 10]), &parameters).unwrap();
+    assert_eq!(packet, Packet::Update(vec![RoutePrefix::IPv4(Ipv4Addr::new(10, 0, 0, 0), 8).into()], Vec::new(), Vec::new()));
+    assert_eq!(diagnostics[0].error.ty(), ErrorType::BGPError(BGPError::update(UpdateMessageError::MissingWellKnownAttribute)));
+
+    // The NLRI is located, even if the length of the attribute overruns the attributes
+    let bytes = [&origin[..], &[0x40, 2, 10, 2][..]].concat();
+    let (packet, diagnostics) = Packet::read_revised(&mut update_bytes(&[], &bytes, &[8, 10]), &parameters).unwrap();
+    assert_eq!(packet, Packet::Update(vec![RoutePrefix::IPv4(Ipv4Addr::new(10, 0, 0, 0), 8).into()], Vec::new(), Vec::new()));
+    assert_eq!(diagnostics[0].type_code, 2);
+    assert_eq!(diagnostics[0].action, ErrorAction::TreatAsWithdraw);
+}
+
+#[test]
+fn test_attribute_validation() {
+    let error_type = |error: UpdateMessageError| ErrorType::BGPError(BGPError::update(error));
+    let mandatory = [0x40, 1, 1, 0, 0x40, 2, 0, 0x40, 3, 4, 10, 0, 0, 1];
+    assert!(Packet::read(&mut update_bytes(&[], &mandatory, &[8, 10])).is_ok());
+    assert!(Packet::read(&mut update_bytes(&[], &[], &[])).is_ok());
+
+    // Origin marked as optional
+    let bytes = [&[0xC0, 1, 1, 0][..], &mandatory[4..]].concat();
+    let error = Packet::read(&mut update_bytes(&[], &bytes, &[8, 10])).unwrap_err();
+    assert_eq!(error.ty(), error_type(UpdateMessageError::AttributeFlagsError));
+
+    // Partial bit on a well-known attribute
+    let bytes = [&[0x60, 1, 1, 0][..], &mandatory[4..]].concat();
+    let error = Packet::read(&mut update_bytes(&[], &bytes, &[8, 10])).unwrap_err();
+    assert_eq!(error.ty(), error_type(UpdateMessageError::AttributeFlagsError));
+
+    let error = Packet::read(&mut update_bytes(&[], &mandatory[..7], &[8, 10])).unwrap_err();
+    assert_eq!(error.ty(), error_type(UpdateMessageError::MissingWellKnownAttribute));
+
+    let bytes = [&mandatory[..], &[0x40, 1, 1, 2][..]].concat();
+    let error = Packet::read(&mut update_bytes(&[], &bytes, &[8, 10])).unwrap_err();
+    assert_eq!(error.ty(), error_type(UpdateMessageError::MalformedAttributeList));
+
+    // The type of the attribute disagrees with the value
+    let attribute = Attribute::new(AttributeType::Origin, AttributeFlags::TRANSITIVE, AttributeValue::LocalPref(100));
+    assert!(attribute.write(&mut Buffer::empty(ByteOrder::BigEndian)).is_err());
+    let attribute = Attribute::new(AttributeType::Origin, AttributeFlags::OPTIONAL, AttributeValue::Origin(Origin::IGP));
+    assert_eq!(attribute.validate_flags().unwrap_err().ty(), error_type(UpdateMessageError::AttributeFlagsError));
+}
+
+#[test]
+fn test_role_capability() {
+    let parameter = OptionalParameter::Capabilities(vec![Capability::Role(Role::Customer)]);
+    let buffer = &mut Buffer::empty(ByteOrder::BigEndian);
+    parameter.write(buffer).unwrap();
+    assert_eq!(buffer.bytes, vec![2, 3, 9, 1, 3]);
+    buffer.reset_position();
+    assert_eq!(OptionalParameter::read(buffer).unwrap(), parameter);
+
+    let mismatch = ErrorType::BGPError(BGPError::open(OpenMessageError::RoleMismatch));
+    assert!(Role::Provider.validate_peer(&[Capability::Role(Role::Customer)], true).is_ok());
+    assert!(Role::Peer.validate_peer(&[], false).is_ok());
+    assert_eq!(Role::Peer.validate_peer(&[], true).unwrap_err().ty(), mismatch);
+    assert_eq!(Role::Provider.validate_peer(&[Capability::Role(Role::Peer)], false).unwrap_err().ty(), mismatch);
+    assert_eq!(Role::RouteServer.validate_peer(&[Capability::Role(Role::RouteServerClient), Capability::Role(Role::Customer)], false).unwrap_err().ty(), mismatch);
+}
+
+#[test]
+fn test_prefix_validation() {
+    let mandatory = [0x40, 1, 1, 0, 0x40, 2, 0, 0x40, 3, 4, 10, 0, 0, 1];
+    let error = Packet::read(&mut update_bytes(&[], &mandatory, &[33, 10, 0, 0, 0, 0])).unwrap_err();
+    assert_eq!(error.ty(), ErrorType::BGPError(BGPError::update(UpdateMessageError::InvalidNetworkField)));
+
+    // Host bits are preserved by default and cleared with the normalisation
+    let packet = Packet::read(&mut update_bytes(&[], &mandatory, &[8, 10, 24, 10, 0, 1])).unwrap();
+    let Packet::Update(_, nlri, _) = &packet else { panic!("Expected Update packet") };
+    assert_eq!(nlri, &vec![RoutePrefix::IPv4(Ipv4Addr::new(10, 0, 0, 0), 8).into(), RoutePrefix::IPv4(Ipv4Addr::new(10, 0, 1, 0), 24).into()]);
+    assert!(packet.duplicate_prefixes(&SessionParameters::default()).is_empty());
+
+    let parameters = SessionParameters { normalize_prefixes: true, ..Default::default() };
+    let packet = Packet::read_with(&mut update_bytes(&[7, 11], &mandatory, &[8, 10, 7, 10, 8, 11]), &parameters).unwrap();
+    let Packet::Update(withdrawn_routes, nlri, _) = &packet else { panic!("Expected Update packet") };
+    assert_eq!(withdrawn_routes, &vec![RoutePrefix::IPv4(Ipv4Addr::new(10, 0, 0, 0), 7).into()]);
+    assert_eq!(nlri, &vec![RoutePrefix::IPv4(Ipv4Addr::new(10, 0, 0, 0), 8).into(), RoutePrefix::IPv4(Ipv4Addr::new(10, 0, 0, 0), 7).into(), RoutePrefix::IPv4(Ipv4Addr::new(11, 0, 0, 0), 8).into()]);
+    assert_eq!(packet.duplicate_prefixes(&parameters), vec![(AFI::IPv4, SAFI::Unicast, RoutePrefix::IPv4(Ipv4Addr::new(10, 0, 0, 0), 7).into())]);
+}
+
+#[test]
+fn test_update_summary() {
+    let nlri = (0..6).map(|index| RoutePrefix::IPv4(Ipv4Addr::new(10, index, 0, 0), 16).into()).collect();
+    let packet = Packet::Update(vec![RoutePrefix::IPv4(Ipv4Addr::new(192, 0, 2, 0), 24).into()], nlri, vec![
+        Attribute::new(AttributeType::Origin, AttributeFlags::TRANSITIVE, AttributeValue::Origin(Origin::IGP)),
+        Attribute::new(AttributeType::LocalPref, AttributeFlags::TRANSITIVE, AttributeValue::LocalPref(100))
+    ]);
+    assert_eq!(packet.summary(), "Update withdrawn=[192.0.2.0/24] announced=[10.0.0.0/16, 10.1.0.0/16, 10.2.0.0/16, 10.3.0.0/16 (+2)] attributes=[Origin, LocalPref]");
+    assert_eq!(Packet::KeepAlive.summary(), "KeepAlive");
+}
+
+#[test]
+fn test_add_path() {
+    let capability = Capability::AddPath(vec![(AFI::IPv4, SAFI::Unicast, AddPathMode::Both), (AFI::IPv6, SAFI::Unicast, AddPathMode::Receive)]);
+    let buffer = &mut Buffer::empty(ByteOrder::BigEndian);
+    capability.write(buffer).unwrap();
+    assert_eq!(buffer.bytes, vec![69, 8, 0, 1, 1, 3, 0, 2, 1, 1]);
+    buffer.reset_position();
+    assert_eq!(Capability::read(buffer).unwrap(), capability);
+
+    let remote = [Capability::AddPath(vec![(AFI::IPv4, SAFI::Unicast, AddPathMode::Send), (AFI::IPv6, SAFI::Unicast, AddPathMode::Send)])];
+    let parameters = SessionParameters::negotiate(&[capability], &remote);
+    assert_eq!(parameters.add_path_receive, vec![(AFI::IPv4, SAFI::Unicast), (AFI::IPv6, SAFI::Unicast)]);
+    assert!(parameters.add_path_send.is_empty());
+
+    let parameters = SessionParameters {
+        add_path_receive: vec![(AFI::IPv4, SAFI::Unicast)],
+        add_path_send: vec![(AFI::IPv4, SAFI::Unicast)],
+        ..Default::default()
+    };
+    let prefix = RoutePrefix::IPv4(Ipv4Addr::new(10, 0, 0, 0), 8);
+    let packet = Packet::Update(vec![PathPrefix::new(prefix, 1)], vec![PathPrefix::new(prefix, 2), PathPrefix::new(prefix, 3)], vec![
+        Attribute::new(AttributeType::Origin, AttributeFlags::TRANSITIVE, AttributeValue::Origin(Origin::IGP)),
+        Attribute::new(AttributeType::ASPath, AttributeFlags::TRANSITIVE, AttributeValue::ASPath(ASPath::new(vec![]))),
+        Attribute::new(AttributeType::NextHop, AttributeFlags::TRANSITIVE, AttributeValue::NextHop(vec![10, 0, 0, 1]))
+    ]);
+    let buffer = &mut Buffer::empty(ByteOrder::BigEndian);
+    packet.write_with(buffer, &parameters).unwrap();
+    assert_eq!(&buffer.bytes[19..28], &[0, 6, 0, 0, 0, 1, 8, 10, 0]);
+    assert_eq!(&buffer.bytes[buffer.len() - 12..], &[0, 0, 0, 2, 8, 10, 0, 0, 0, 3, 8, 10]);
+    buffer.reset_position();
+    assert_eq!(Packet::read_with(buffer, &parameters).unwrap(), packet);
+    assert!(packet.duplicate_prefixes(&parameters).is_empty());
+
+    // Without ADD-PATH the path identifiers aren't compared
+    assert_eq!(packet.duplicate_prefixes(&SessionParameters::default()), vec![(AFI::IPv4, SAFI::Unicast, prefix.into())]);
+
+    // Path identifiers can't be written without ADD-PATH
+    assert!(packet.write(&mut Buffer::empty(ByteOrder::BigEndian)).is_err());
+}
+
+#[test]
+fn test_multiple_labels() {
+    let capability = Capability::MultipleLabels(vec![(AFI::IPv4, SAFI::LabeledUnicast, 3), (AFI::IPv6, SAFI::LabeledUnicast, 2)]);
+    let buffer = &mut Buffer::empty(ByteOrder::BigEndian);
+    capability.write(buffer).unwrap();
+    assert_eq!(buffer.bytes, vec![8, 8, 0, 1, 4, 3, 0, 2, 4, 2]);
+    buffer.reset_position();
+    assert_eq!(Capability::read(buffer).unwrap(), capability);
+
+    // The number of labels is limited by the capability of the peer
+    let remote = [Capability::MultipleLabels(vec![(AFI::IPv4, SAFI::LabeledUnicast, 2)])];
+    let parameters = SessionParameters::negotiate(&[capability], &remote);
+    assert_eq!(parameters.multiple_labels, vec![(AFI::IPv4, SAFI::LabeledUnicast, 2)]);
+}
+
+#[cfg(feature = "bgp_multiprotocol")]
+#[test]
+fn test_multiprotocol_duplicate_prefixes() {
+    use crate::bgp::path_attr::MPNLRI;
+    use crate::bgp::LabeledPrefix;
+
+    let prefix = RoutePrefix::IPv4(Ipv4Addr::new(10, 0, 0, 0), 8);
+    let reachable = |safi, nlri| Attribute::new(AttributeType::MPReachableNLRI, AttributeFlags::OPTIONAL, AttributeValue::MPReachableNLRI(AFI::IPv4, safi, vec![10, 0, 0, 1], nlri));
+    let unreachable = |safi, nlri| Attribute::new(AttributeType::MPUnreachableNLRI, AttributeFlags::OPTIONAL, AttributeValue::MPUnreachableNLRI(AFI::IPv4, safi, nlri));
+
+    // The same prefix of different address families isn't a duplicate
+    let packet = Packet::Update(Vec::new(), Vec::new(), vec![
+        reachable(SAFI::Multicast, MPNLRI::Prefixes(vec![prefix.into()])),
+        unreachable(SAFI::LabeledUnicast, MPNLRI::LabeledPrefixes(vec![LabeledPrefix::withdrawal(prefix)]))
+    ]);
+    assert!(packet.duplicate_prefixes(&SessionParameters::default()).is_empty());
+
+    // The NLRI of the Update packet and the IPv4 unicast NLRI of the attributes are compared
+    let packet = Packet::Update(Vec::new(), vec![prefix.into()], vec![
+        unreachable(SAFI::Unicast, MPNLRI::Prefixes(vec![prefix.into()]))
+    ]);
+    assert_eq!(packet.duplicate_prefixes(&SessionParameters::default()), vec![(AFI::IPv4, SAFI::Unicast, prefix.into())]);
+}
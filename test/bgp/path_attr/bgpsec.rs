@@ -0,0 +1,111 @@
+use crate::bgp::error::{BGPError, UpdateMessageError};
+use crate::bgp::opt_params::{AFI, BGPsecDirection, Capability, OptionalParameter};
+use crate::bgp::path_attr::bgpsec::{BGPsecPath, SecurePathSegment, SignatureBlock, SignatureSegment, ALGORITHM_SUITE_P256_SHA256};
+use crate::bgp::path_attr::{ASPath, ASPathSegment, Attribute, AttributeFlags, AttributeType, AttributeValue};
+use crate::error::ErrorType;
+use crate::if_no_std;
+use crate::io::{Buffer, ByteOrder, WriteRead};
+use super::write_read;
+
+if_no_std! {
+    use alloc::vec;
+}
+
+fn segment(autonomous_system: u32) -> SecurePathSegment {
+    SecurePathSegment {
+        pcount: 1,
+        flags: 0,
+        autonomous_system
+    }
+}
+
+#[test]
+fn test_bgpsec_capability() {
+    let parameter = OptionalParameter::Capabilities(vec![
+        Capability::BGPsec(0, BGPsecDirection::Send, AFI::IPv4),
+        Capability::BGPsec(0, BGPsecDirection::Receive, AFI::IPv6)
+    ]);
+    let buffer = &mut Buffer::empty(ByteOrder::BigEndian);
+    parameter.write(buffer).unwrap();
+    assert_eq!(buffer.bytes, vec![2, 10, 7, 3, 0x08, 0, 1, 7, 3, 0x00, 0, 2]);
+    buffer.reset_position();
+    assert_eq!(OptionalParameter::read(buffer).unwrap(), parameter);
+}
+
+#[test]
+fn test_bgpsec_path() {
+    let path = BGPsecPath {
+        secure_path: vec![segment(65002), SecurePathSegment { pcount: 2, flags: SecurePathSegment::CONFED_SEGMENT, autonomous_system: 65001 }],
+        signature_blocks: vec![SignatureBlock {
+            algorithm_suite: ALGORITHM_SUITE_P256_SHA256,
+            segments: vec![
+                SignatureSegment { subject_key_identifier: [2; 20], signature: vec![1, 2, 3] },
+                SignatureSegment { subject_key_identifier: [1; 20], signature: vec![4, 5] }
+            ]
+        }]
+    };
+    let attribute = Attribute::new(AttributeType::BGPSecPath, AttributeFlags::OPTIONAL, AttributeValue::BGPsecPath(path.clone()));
+    let buffer = &mut Buffer::empty(ByteOrder::BigEndian);
+    attribute.write(buffer).unwrap();
+    assert_eq!(&buffer.bytes[..17], &[0x80, 33, 66, 0, 14, 1, 0, 0, 0, 0xFD, 0xEA, 2, 0x80, 0, 0, 0xFD, 0xE9]);
+    assert_eq!(&buffer.bytes[17..20], &[0, 52, 1]);
+    assert_eq!(write_read(&attribute), attribute);
+
+    assert_eq!(path.to_as_path(), ASPath::new(vec![
+        ASPathSegment::ASSequence(vec![65002]),
+        ASPathSegment::ASConfedSequence(vec![65001, 65001])
+    ]));
+}
+
+#[test]
+fn test_malformed_bgpsec_path() {
+    // The Signature_Block contains no Signature Segment for the single Secure_Path Segment
+    let buffer = &mut Buffer::from_vec(vec![0x80, 33, 11, 0, 8, 1, 0, 0, 0, 0xFD, 0xEA, 0, 3, 1], ByteOrder::BigEndian);
+    assert_eq!(Attribute::read(buffer).unwrap_err().ty(), ErrorType::BGPError(BGPError::update(UpdateMessageError::MalformedASPath)));
+
+    // The Secure_Path is empty
+    let buffer = &mut Buffer::from_vec(vec![0x80, 33, 2, 0, 2], ByteOrder::BigEndian);
+    assert_eq!(Attribute::read(buffer).unwrap_err().ty(), ErrorType::BGPError(BGPError::update(UpdateMessageError::MalformedASPath)));
+}
+
+#[cfg(feature = "bgpsec")]
+#[test]
+fn test_bgpsec_verification() {
+    use crate::bgp::opt_params::SAFI;
+    use crate::bgp::path_attr::bgpsec::{BGPsecValidation, RouterKey};
+    use p256::ecdsa::signature::Signer;
+    use p256::ecdsa::{Signature, SigningKey};
+
+    let origin_key = SigningKey::from_slice(&[1; 32]).unwrap();
+    let transit_key = SigningKey::from_slice(&[2; 32]).unwrap();
+    let keys = [
+        RouterKey { autonomous_system: 65001, subject_key_identifier: [1; 20], public_key: origin_key.verifying_key().to_encoded_point(false).as_bytes().to_vec() },
+        RouterKey { autonomous_system: 65002, subject_key_identifier: [2; 20], public_key: transit_key.verifying_key().to_encoded_point(false).as_bytes().to_vec() }
+    ];
+    let nlri = [24, 192, 0, 2];
+
+    // The origin AS 65001 signs the update for AS 65002, which signs the update for AS 65003
+    let mut path = BGPsecPath {
+        secure_path: vec![segment(65002), segment(65001)],
+        signature_blocks: vec![SignatureBlock {
+            algorithm_suite: ALGORITHM_SUITE_P256_SHA256,
+            segments: vec![
+                SignatureSegment { subject_key_identifier: [2; 20], signature: vec![] },
+                SignatureSegment { subject_key_identifier: [1; 20], signature: vec![] }
+            ]
+        }]
+    };
+    for (index, key, target_as) in [(1, &origin_key, 65002), (0, &transit_key, 65003)] {
+        let data = path.signed_data(&path.signature_blocks[0], index, target_as, AFI::IPv4, SAFI::Unicast, &nlri).unwrap();
+        let signature: Signature = key.sign(&data);
+        path.signature_blocks[0].segments[index].signature = signature.to_der().as_bytes().to_vec();
+    }
+
+    assert!(path.verify(65003, AFI::IPv4, SAFI::Unicast, &nlri, &keys).is_valid());
+    assert_eq!(path.verify(65004, AFI::IPv4, SAFI::Unicast, &nlri, &keys), BGPsecValidation::InvalidSignature(65002));
+    assert_eq!(path.verify(65003, AFI::IPv4, SAFI::Unicast, &[24, 192, 0, 3], &keys), BGPsecValidation::InvalidSignature(65002));
+    assert_eq!(path.verify(65003, AFI::IPv4, SAFI::Unicast, &nlri, &keys[1..]), BGPsecValidation::MissingRouterKey(65001));
+
+    path.signature_blocks[0].algorithm_suite = 2;
+    assert_eq!(path.verify(65003, AFI::IPv4, SAFI::Unicast, &nlri, &keys), BGPsecValidation::UnsupportedAlgorithmSuite);
+}
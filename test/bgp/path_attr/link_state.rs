@@ -0,0 +1,128 @@
+use core::net::{Ipv4Addr, Ipv6Addr};
+use crate::bgp::error::{BGPError, UpdateMessageError};
+use crate::bgp::path_attr::link_state::{Bandwidth, LinkDescriptor, LinkStateAttributeTlv, LinkStateNLRI, LinkStateProtocol, NodeDescriptor, PrefixDescriptor, SIDLabel, SRCapabilityRange};
+use crate::bgp::path_attr::{Attribute, AttributeFlags, AttributeType, AttributeValue};
+use crate::error::ErrorType;
+use crate::if_no_std;
+use crate::io::{Buffer, ByteOrder, WriteRead};
+use super::write_read;
+
+if_no_std! {
+    use alloc::{string::ToString, vec, vec::Vec};
+}
+
+fn local_node() -> Vec<NodeDescriptor> {
+    vec![
+        NodeDescriptor::AutonomousSystem(64600),
+        NodeDescriptor::BGPLSIdentifier(0),
+        NodeDescriptor::IGPRouterId(vec![0x19, 0x21, 0x68, 0x00, 0x10, 0x01])
+    ]
+}
+
+#[test]
+fn test_link_state_nlri() {
+    let nlri = LinkStateNLRI::Node {
+        protocol: LinkStateProtocol::ISISLevel2,
+        identifier: 0,
+        local_node: vec![NodeDescriptor::AutonomousSystem(64600)]
+    };
+    let buffer = &mut Buffer::empty(ByteOrder::BigEndian);
+    nlri.write(buffer).unwrap();
+    assert_eq!(buffer.bytes, vec![
+        0, 1, 0, 21, 2, 0, 0, 0, 0, 0, 0, 0, 0,
+        1, 0, 0, 8, 2, 0, 0, 4, 0, 0, 0xFC, 0x58
+    ]);
+    buffer.reset_position();
+    assert_eq!(LinkStateNLRI::read(buffer).unwrap(), nlri);
+
+    for nlri in [
+        LinkStateNLRI::Link {
+            protocol: LinkStateProtocol::OSPFv2,
+            identifier: 1,
+            local_node: local_node(),
+            remote_node: vec![NodeDescriptor::OSPFAreaId(0), NodeDescriptor::IGPRouterId(vec![192, 0, 2, 2])],
+            link: vec![
+                LinkDescriptor::LinkIdentifiers(1, 2),
+                LinkDescriptor::IPv4InterfaceAddress(Ipv4Addr::new(10, 0, 0, 1)),
+                LinkDescriptor::IPv4NeighborAddress(Ipv4Addr::new(10, 0, 0, 2)),
+                LinkDescriptor::IPv6InterfaceAddress(Ipv6Addr::LOCALHOST),
+                LinkDescriptor::IPv6NeighborAddress(Ipv6Addr::UNSPECIFIED),
+                LinkDescriptor::MultiTopologyId(vec![0, 2]),
+                LinkDescriptor::Unknown(300, vec![1])
+            ]
+        },
+        LinkStateNLRI::IPv4Prefix {
+            protocol: LinkStateProtocol::Direct,
+            identifier: 0,
+            local_node: local_node(),
+            prefix: vec![PrefixDescriptor::OSPFRouteType(1), PrefixDescriptor::IPReachability(24, vec![10, 0, 0])]
+        },
+        LinkStateNLRI::IPv6Prefix {
+            protocol: LinkStateProtocol::BGP,
+            identifier: 0,
+            local_node: vec![NodeDescriptor::BGPRouterId(Ipv4Addr::new(192, 0, 2, 1)), NodeDescriptor::MemberAutonomousSystem(65001)],
+            prefix: vec![PrefixDescriptor::MultiTopologyId(vec![2]), PrefixDescriptor::IPReachability(0, vec![])]
+        },
+        LinkStateNLRI::Unknown(6, vec![1, 2, 3])
+    ] {
+        let buffer = &mut Buffer::empty(ByteOrder::BigEndian);
+        nlri.write(buffer).unwrap();
+        buffer.reset_position();
+        assert_eq!(LinkStateNLRI::read(buffer).unwrap(), nlri);
+    }
+}
+
+#[cfg(feature = "bgp_multiprotocol")]
+#[test]
+fn test_link_state_address_family() {
+    use crate::bgp::opt_params::{AFI, SAFI};
+    use crate::bgp::path_attr::MPNLRI;
+
+    let attribute = Attribute::new(AttributeType::MPReachableNLRI, AttributeFlags::OPTIONAL, AttributeValue::MPReachableNLRI(
+        AFI::LinkState, SAFI::LinkState, vec![192, 0, 2, 1], MPNLRI::LinkState(vec![
+            LinkStateNLRI::Node { protocol: LinkStateProtocol::ISISLevel1, identifier: 0, local_node: local_node() },
+            LinkStateNLRI::Node { protocol: LinkStateProtocol::ISISLevel1, identifier: 0, local_node: vec![] }
+        ])
+    ));
+    let buffer = &mut Buffer::empty(ByteOrder::BigEndian);
+    attribute.write(buffer).unwrap();
+    assert_eq!(&buffer.bytes[3..6], &[0x40, 0x04, 71]);
+    assert_eq!(write_read(&attribute), attribute);
+}
+
+#[test]
+fn test_link_state_attribute() {
+    let attribute = Attribute::new(AttributeType::BGPLSAttribute, AttributeFlags::OPTIONAL, AttributeValue::LinkState(vec![
+        LinkStateAttributeTlv::NodeFlags(0x20),
+        LinkStateAttributeTlv::NodeName("router-1".to_string()),
+        LinkStateAttributeTlv::LocalIPv4RouterId(Ipv4Addr::new(192, 0, 2, 1)),
+        LinkStateAttributeTlv::LocalIPv6RouterId(Ipv6Addr::LOCALHOST),
+        LinkStateAttributeTlv::RemoteIPv4RouterId(Ipv4Addr::new(192, 0, 2, 2)),
+        LinkStateAttributeTlv::RemoteIPv6RouterId(Ipv6Addr::UNSPECIFIED),
+        LinkStateAttributeTlv::SRCapabilities(0x80, vec![
+            SRCapabilityRange { range_size: 8000, first_sid: SIDLabel::Label(16000) },
+            SRCapabilityRange { range_size: 100, first_sid: SIDLabel::Index(0) }
+        ]),
+        LinkStateAttributeTlv::AdministrativeGroup(1),
+        LinkStateAttributeTlv::MaximumLinkBandwidth(Bandwidth::from_bytes_per_second(1.25e9)),
+        LinkStateAttributeTlv::MaximumReservableBandwidth(Bandwidth::from_bytes_per_second(1e9)),
+        LinkStateAttributeTlv::UnreservedBandwidth([Bandwidth::from_bytes_per_second(1e9); 8]),
+        LinkStateAttributeTlv::TEDefaultMetric(10),
+        LinkStateAttributeTlv::IGPMetric(10, 3),
+        LinkStateAttributeTlv::IGPMetric(10, 1),
+        LinkStateAttributeTlv::IGPFlags(0x10),
+        LinkStateAttributeTlv::PrefixMetric(20),
+        LinkStateAttributeTlv::Unknown(1035, vec![0, 1])
+    ]));
+    assert_eq!(write_read(&attribute), attribute);
+
+    let buffer = &mut Buffer::empty(ByteOrder::BigEndian);
+    LinkStateAttributeTlv::IGPMetric(0x0102, 2).write(buffer).unwrap();
+    assert_eq!(buffer.bytes, vec![0x04, 0x47, 0, 2, 1, 2]);
+    assert_eq!(Bandwidth(0x4E9502F9).bytes_per_second(), 1.25e9);
+    assert!(LinkStateAttributeTlv::IGPMetric(0x0102, 1).write(buffer).is_err());
+
+    // The router ID has a fixed length
+    let buffer = &mut Buffer::from_vec(vec![0x80, 29, 7, 0x04, 0x04, 0, 3, 192, 0, 2], ByteOrder::BigEndian);
+    assert_eq!(Attribute::read(buffer).unwrap_err().ty(), ErrorType::BGPError(BGPError::update(UpdateMessageError::OptionalAttributeError)));
+}
@@ -0,0 +1,571 @@
+use core::cmp::Ordering;
+use core::net::{Ipv4Addr, Ipv6Addr};
+use core::str::FromStr;
+use crate::bgp::opt_params::Role;
+use crate::bgp::SessionParameters;
+use crate::bgp::error::{BGPError, UpdateMessageError};
+use crate::error::ErrorType;
+use crate::bgp::path_attr::{accumulated_igp_metric, compare_accumulated_igp_metric, increment_accumulated_igp_metric, insert_originator_id, is_reflection_loop, only_to_customer_egress, only_to_customer_ingress, pop_attribute_set, prepend_cluster_id, propagate_unknown_attributes, push_attribute_set, reconstruct_as4_attributes, translate_as4_attributes, AIGPTlv, ASPath, ASPathSegment, Attribute, AttributeFlags, AttributeType, AttributeValue, Community, ExtendedCommunity, ExtendedCommunitySubType, Ipv6ExtendedCommunity, LargeCommunity, LargeCommunityFunction, Origin};
+use crate::if_no_std;
+use crate::io::{Buffer, ByteOrder, WriteRead};
+
+pub mod bgpsec;
+pub mod link_state;
+pub mod pmsi_tunnel;
+pub mod prefix_sid;
+pub mod tunnel_encap;
+
+if_no_std! {
+    use alloc::{string::ToString, vec, vec::Vec};
+}
+
+pub fn write_read(attribute: &Attribute) -> Attribute {
+    let buffer = &mut Buffer::empty(ByteOrder::BigEndian);
+    attribute.write(buffer).unwrap();
+    buffer.reset_position();
+    Attribute::read(buffer).unwrap()
+}
+
+pub fn write_read_with(attribute: &Attribute, parameters: &SessionParameters) -> Attribute {
+    let buffer = &mut Buffer::empty(ByteOrder::BigEndian);
+    attribute.write_with(buffer, parameters).unwrap();
+    buffer.reset_position();
+    Attribute::read_with(buffer, parameters).unwrap()
+}
+
+#[test]
+fn test_route_reflection_attributes() {
+    let originator_id = Attribute::new(AttributeType::OriginatorId, AttributeFlags::OPTIONAL, AttributeValue::OriginatorId(Ipv4Addr::new(192, 0, 2, 1)));
+    let cluster_list = Attribute::new(AttributeType::ClusterList, AttributeFlags::OPTIONAL, AttributeValue::ClusterList(vec![1, 2, 3]));
+    assert_eq!(originator_id, write_read(&originator_id));
+    assert_eq!(cluster_list, write_read(&cluster_list));
+}
+
+#[test]
+fn test_route_reflection_loop() {
+    let router_id = Ipv4Addr::new(192, 0, 2, 1);
+    let mut attributes = Vec::new();
+    insert_originator_id(&mut attributes, Ipv4Addr::new(192, 0, 2, 2));
+    insert_originator_id(&mut attributes, router_id);
+    prepend_cluster_id(&mut attributes, 10);
+    prepend_cluster_id(&mut attributes, 20);
+
+    assert_eq!(attributes[0].value(), &AttributeValue::OriginatorId(Ipv4Addr::new(192, 0, 2, 2)));
+    assert_eq!(attributes[1].value(), &AttributeValue::ClusterList(vec![20, 10]));
+    assert!(!is_reflection_loop(&attributes, router_id, 30));
+    assert!(is_reflection_loop(&attributes, router_id, 10));
+    assert!(is_reflection_loop(&attributes, Ipv4Addr::new(192, 0, 2, 2), 30));
+}
+
+#[test]
+fn test_communities() {
+    let attribute = Attribute::new(AttributeType::Community, AttributeFlags::OPTIONAL | AttributeFlags::TRANSITIVE, AttributeValue::Communities(vec![
+        Community::new(65000, 100),
+        Community::BLACKHOLE
+    ]));
+    let buffer = &mut Buffer::empty(ByteOrder::BigEndian);
+    attribute.write(buffer).unwrap();
+    assert_eq!(buffer.bytes, vec![0xC0, 8, 8, 0xFD, 0xE8, 0, 100, 0xFF, 0xFF, 0x02, 0x9A]);
+    assert_eq!(write_read(&attribute), attribute);
+
+    assert_eq!(u32::from(Community::NO_EXPORT), 0xFFFFFF01);
+    assert_eq!(Community::from(0xFFFFFF04), Community::NO_PEER);
+    assert_eq!(Community::from_str("65000:100").unwrap(), Community::new(65000, 100));
+    assert_eq!(Community::from_str("no-export-subconfed").unwrap(), Community::NO_EXPORT_SUBCONFED);
+    assert_eq!(Community::from_str("65535:0").unwrap().to_string(), "graceful-shutdown");
+    assert_eq!(Community::NO_ADVERTISE.to_string(), "no-advertise");
+    assert_eq!(Community::new(65000, 100).to_string(), "65000:100");
+    assert!(Community::from_str("65536:1").is_err());
+    assert!(Community::from_str("no-export:1").is_err());
+
+    let mut communities = vec![Community::NO_EXPORT, Community::new(65000, 200), Community::new(100, 65000)];
+    communities.sort();
+    assert_eq!(communities, vec![Community::new(100, 65000), Community::new(65000, 200), Community::NO_EXPORT]);
+}
+
+#[test]
+fn test_large_communities() {
+    let attribute = Attribute::new(AttributeType::LargeCommunity, AttributeFlags::OPTIONAL | AttributeFlags::TRANSITIVE, AttributeValue::LargeCommunities(vec![
+        LargeCommunity::new(4200000000, 1, 2)
+    ]));
+    let buffer = &mut Buffer::empty(ByteOrder::BigEndian);
+    attribute.write(buffer).unwrap();
+    assert_eq!(buffer.bytes, vec![0xC0, 32, 12, 0xFA, 0x56, 0xEA, 0, 0, 0, 0, 1, 0, 0, 0, 2]);
+    assert_eq!(write_read(&attribute), attribute);
+
+    assert_eq!(LargeCommunity::from_str("4200000000:1:2").unwrap(), LargeCommunity::new(4200000000, 1, 2));
+    assert_eq!(LargeCommunity::new(64600, 0, 4294967295).to_string(), "64600:0:4294967295");
+    assert!(LargeCommunity::from_str("1:2").is_err());
+    assert!(LargeCommunity::from_str("1:2:3:4").is_err());
+    assert!(LargeCommunity::from_str("1:2:4294967296").is_err());
+
+    let community = LargeCommunity::with_function(2914, LargeCommunityFunction::NoExportToAS(65536));
+    assert_eq!(community, LargeCommunity::new(2914, 4, 65536));
+    assert_eq!(community.function(), LargeCommunityFunction::NoExportToAS(65536));
+    assert!(community.function().is_action());
+    assert!(!LargeCommunity::new(2914, 1, 528).function().is_action());
+    assert_eq!(LargeCommunity::new(2914, 100, 1).function(), LargeCommunityFunction::Unknown(100, 1));
+}
+
+#[test]
+fn test_extended_communities() {
+    let communities = vec![
+        ExtendedCommunity::TwoOctetAS(true, ExtendedCommunitySubType::RouteTarget, 65000, 100),
+        ExtendedCommunity::IPv4Address(true, ExtendedCommunitySubType::RouteOrigin, Ipv4Addr::new(192, 0, 2, 1), 100),
+        ExtendedCommunity::FourOctetAS(false, ExtendedCommunitySubType::RouteTarget, 4200000000, 100),
+        ExtendedCommunity::Opaque(true, 0x0b, [0, 0, 0, 0, 0, 100]),
+        ExtendedCommunity::Unknown([0x06, 0x00, 0, 0, 0, 0, 0, 1])
+    ];
+    let attribute = Attribute::new(AttributeType::ExtendedCommunities, AttributeFlags::OPTIONAL | AttributeFlags::TRANSITIVE, AttributeValue::ExtendedCommunities(communities));
+    assert_eq!(attribute, write_read(&attribute));
+}
+
+#[test]
+fn test_extended_community_text() {
+    for text in ["rt:65000:100", "ro:192.0.2.1:100", "rt:4200000000:100", "rt:65000L:100", "0x4002fa56ea000064"] {
+        assert_eq!(text, ExtendedCommunity::from_str(text).unwrap().to_string());
+    }
+
+    assert_eq!(
+        ExtendedCommunity::from_str("target:65000:100").unwrap(),
+        ExtendedCommunity::TwoOctetAS(true, ExtendedCommunitySubType::RouteTarget, 65000, 100)
+    );
+    assert!(ExtendedCommunity::from_str("rt:65000").is_err());
+    assert!(ExtendedCommunity::from_str("rt:4200000000:70000").is_err());
+}
+
+#[test]
+fn test_ipv6_extended_communities() {
+    let address = Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 1);
+    let attribute = Attribute::new(AttributeType::Ipv6AddressSpecifiedExtendedCommunity, AttributeFlags::OPTIONAL | AttributeFlags::TRANSITIVE, AttributeValue::Ipv6ExtendedCommunities(vec![
+        Ipv6ExtendedCommunity::new(true, ExtendedCommunitySubType::RouteTarget, address, 100),
+        Ipv6ExtendedCommunity::new(false, ExtendedCommunitySubType::RouteOrigin, address, 200)
+    ]));
+    assert_eq!(attribute, write_read(&attribute));
+
+    for text in ["rt:[2001:db8::1]:100", "ro:[2001:db8::1]:100", "0x400320010db8000000000000000000000001ffff"] {
+        assert_eq!(text, Ipv6ExtendedCommunity::from_str(text).unwrap().to_string());
+    }
+    assert_eq!(
+        Ipv6ExtendedCommunity::from_str("rt:2001:db8::1:100").unwrap(),
+        Ipv6ExtendedCommunity::new(true, ExtendedCommunitySubType::RouteTarget, address, 100)
+    );
+    assert!(Ipv6ExtendedCommunity::from_str("rt:[192.0.2.1]:100").is_err());
+
+    // Communities of unknown types are preserved with the raw bytes
+    let mut bytes = vec![AttributeFlags::OPTIONAL.bits() | AttributeFlags::TRANSITIVE.bits(), 25, 20, 0x01, 0x02];
+    bytes.extend_from_slice(&[0xAB; 18]);
+    let attribute = Attribute::read(&mut Buffer::from_vec(bytes.clone(), ByteOrder::BigEndian)).unwrap();
+    let mut community = [0xAB; 20];
+    community[..2].copy_from_slice(&[0x01, 0x02]);
+    assert_eq!(attribute.value(), &AttributeValue::Ipv6ExtendedCommunities(vec![Ipv6ExtendedCommunity::Unknown(community)]));
+    let buffer = &mut Buffer::empty(ByteOrder::BigEndian);
+    attribute.write(buffer).unwrap();
+    assert_eq!(buffer.bytes, bytes);
+}
+
+#[test]
+fn test_as4_attributes() {
+    let transitive = AttributeFlags::OPTIONAL | AttributeFlags::TRANSITIVE;
+    let as4_path = Attribute::new(AttributeType::AS4Path, transitive, AttributeValue::AS4Path(ASPath::new(vec![ASPathSegment::ASSequence(vec![4200000000, 65000])])));
+    let as4_aggregator = Attribute::new(AttributeType::AS4Aggregator, transitive, AttributeValue::AS4Aggregator(4200000000, Ipv4Addr::new(192, 0, 2, 1)));
+    let aggregator = Attribute::new(AttributeType::Aggregator, transitive, AttributeValue::Aggregator(65000, Ipv4Addr::new(192, 0, 2, 1)));
+    assert_eq!(as4_path, write_read(&as4_path));
+    assert_eq!(as4_aggregator, write_read(&as4_aggregator));
+    assert_eq!(aggregator, write_read(&aggregator));
+
+    let parameters = SessionParameters { four_octet_as: false, ..Default::default() };
+    assert_eq!(aggregator, write_read_with(&aggregator, &parameters));
+
+    // The AS4_AGGREGATOR attribute always contains a four-octet AS number
+    let buffer = &mut Buffer::empty(ByteOrder::BigEndian);
+    as4_aggregator.write_with(buffer, &parameters).unwrap();
+    assert_eq!(buffer.bytes, vec![0xC0, 18, 8, 0xFA, 0x56, 0xEA, 0x00, 192, 0, 2, 1]);
+    let buffer = &mut Buffer::empty(ByteOrder::BigEndian);
+    aggregator.write_with(buffer, &parameters).unwrap();
+    assert_eq!(buffer.bytes, vec![0xC0, 7, 6, 0xFD, 0xE8, 192, 0, 2, 1]);
+}
+
+#[test]
+fn test_as4_path_reconstruction() {
+    let transitive = AttributeFlags::OPTIONAL | AttributeFlags::TRANSITIVE;
+    let original = vec![
+        Attribute::new(AttributeType::ASPath, AttributeFlags::TRANSITIVE, AttributeValue::ASPath(ASPath::new(vec![ASPathSegment::ASSequence(vec![65001, 4200000000, 65002])]))),
+        Attribute::new(AttributeType::Aggregator, transitive, AttributeValue::Aggregator(4200000001, Ipv4Addr::new(192, 0, 2, 1)))
+    ];
+
+    let mut attributes = original.clone();
+    translate_as4_attributes(&mut attributes);
+    assert_eq!(attributes.len(), 4);
+    assert_eq!(attributes[0].value(), &AttributeValue::ASPath(ASPath::new(vec![ASPathSegment::ASSequence(vec![65001, 23456, 65002])])));
+    assert_eq!(attributes[1].value(), &AttributeValue::Aggregator(23456, Ipv4Addr::new(192, 0, 2, 1)));
+
+    // Transfer the attributes over a session without support for four-octet AS numbers
+    let parameters = SessionParameters { four_octet_as: false, ..Default::default() };
+    let mut attributes: Vec<Attribute> = attributes.iter().map(|attribute| write_read_with(attribute, &parameters)).collect();
+
+    // Simulate an old router, which prepends its AS number
+    if let AttributeValue::ASPath(path) = attributes[0].value_mut() {
+        path.prepend(65003);
+    }
+
+    reconstruct_as4_attributes(&mut attributes);
+    assert_eq!(attributes, vec![
+        Attribute::new(AttributeType::ASPath, AttributeFlags::TRANSITIVE, AttributeValue::ASPath(ASPath::new(vec![ASPathSegment::ASSequence(vec![65003, 65001, 4200000000, 65002])]))),
+        original[1].clone()
+    ]);
+}
+
+#[test]
+fn test_as_path_segments() {
+    let path = ASPath::new(vec![
+        ASPathSegment::ASConfedSequence(vec![64512, 64513]),
+        ASPathSegment::ASConfedSet(vec![64514]),
+        ASPathSegment::ASSequence(vec![65000, 65001]),
+        ASPathSegment::ASSet(vec![65002, 65003])
+    ]);
+    let attribute = Attribute::new(AttributeType::ASPath, AttributeFlags::TRANSITIVE, AttributeValue::ASPath(path.clone()));
+    assert_eq!(attribute, write_read(&attribute));
+    assert_eq!(path.path_length(), 3);
+
+    // The sequence with 300 AS numbers is split into two segments on the wire
+    let path = ASPath::new(vec![ASPathSegment::ASSequence((1..=300).collect())]);
+    let buffer = &mut Buffer::empty(ByteOrder::BigEndian);
+    path.write(buffer).unwrap();
+    assert_eq!(&buffer.bytes[0..2], &[2, 255]);
+    assert_eq!(&buffer.bytes[2 + 255 * 4..4 + 255 * 4], &[2, 45]);
+    buffer.reset_position();
+    assert_eq!(path, ASPath::read(buffer).unwrap());
+    assert_eq!(path.path_length(), 300);
+
+    // The confederation sequence is split too, but sets with more than 255 AS numbers are rejected
+    let path = ASPath::new(vec![ASPathSegment::ASConfedSequence((1..=256).collect())]);
+    let buffer = &mut Buffer::empty(ByteOrder::BigEndian);
+    path.write(buffer).unwrap();
+    assert_eq!(&buffer.bytes[0..2], &[3, 255]);
+    assert_eq!(&buffer.bytes[2 + 255 * 4..4 + 255 * 4], &[3, 1]);
+    assert!(ASPath::new(vec![ASPathSegment::ASSet((1..=255).collect())]).write(&mut Buffer::empty(ByteOrder::BigEndian)).is_ok());
+    assert!(ASPath::new(vec![ASPathSegment::ASSet((1..=256).collect())]).write(&mut Buffer::empty(ByteOrder::BigEndian)).is_err());
+    assert!(ASPath::new(vec![ASPathSegment::ASConfedSet((1..=256).collect())]).write(&mut Buffer::empty(ByteOrder::BigEndian)).is_err());
+}
+
+#[test]
+fn test_as_path_helpers() {
+    let mut path = ASPath::default();
+    assert_eq!(path.path_length(), 0);
+    path.prepend(65002);
+    path.prepend(65001);
+    path.prepend_confederation(64512);
+    assert_eq!(path.segments(), &[ASPathSegment::ASConfedSequence(vec![64512]), ASPathSegment::ASSequence(vec![65001, 65002])]);
+    assert_eq!(path.path_length(), 2);
+    assert!(path.contains(64512));
+    assert!(path.contains(65002));
+    assert!(!path.contains(65003));
+
+    path.remove_confederation_segments();
+    path.prepend(65000);
+    assert_eq!(path.segments(), &[ASPathSegment::ASSequence(vec![65000, 65001, 65002])]);
+}
+
+#[test]
+fn test_malformed_as_path() {
+    for bytes in [vec![5, 1, 0, 0, 0, 1], vec![2, 0], vec![2, 2, 0, 0, 0, 1]] {
+        let buffer = &mut Buffer::from_vec(bytes, ByteOrder::BigEndian);
+        let error = ASPath::read(buffer).unwrap_err();
+        assert_eq!(error.ty(), ErrorType::BGPError(BGPError::update(UpdateMessageError::MalformedASPath)));
+    }
+}
+
+#[test]
+fn test_extended_length() {
+    let path = ASPath::new(vec![ASPathSegment::ASSequence((1..=300).collect())]);
+    let attribute = Attribute::new(AttributeType::ASPath, AttributeFlags::TRANSITIVE, AttributeValue::ASPath(path.clone()));
+    let buffer = &mut Buffer::empty(ByteOrder::BigEndian);
+    attribute.write(buffer).unwrap();
+    assert_eq!(&buffer.bytes[0..4], &[0b01010000, 2, 0x04, 0xB4]);
+    assert_eq!(buffer.len(), 4 + 1204);
+
+    buffer.reset_position();
+    let attribute_read = Attribute::read(buffer).unwrap();
+    assert_eq!(attribute_read.flags(), AttributeFlags::TRANSITIVE | AttributeFlags::EXTENDED_LENGTH);
+    assert_eq!(attribute_read.value(), &AttributeValue::ASPath(path));
+
+    // The Extended Length bit is kept for short values, if it is set by the caller
+    let attribute = Attribute::new(AttributeType::ClusterList, AttributeFlags::OPTIONAL | AttributeFlags::EXTENDED_LENGTH, AttributeValue::ClusterList(vec![1]));
+    let buffer = &mut Buffer::empty(ByteOrder::BigEndian);
+    attribute.write(buffer).unwrap();
+    assert_eq!(&buffer.bytes[0..4], &[0b10010000, 10, 0, 4]);
+    assert_eq!(attribute, write_read(&attribute));
+}
+
+#[test]
+fn test_attribute_type_codes() {
+    for value in 0..=u8::MAX {
+        assert_eq!(value, u8::from(AttributeType::from(value)));
+    }
+    assert_eq!(AttributeType::from(200), AttributeType::Unknown(200));
+}
+
+#[test]
+fn test_aigp_attribute() {
+    let attribute = Attribute::new(AttributeType::AIGP, AttributeFlags::OPTIONAL, AttributeValue::AIGP(vec![
+        AIGPTlv::AccumulatedMetric(100),
+        AIGPTlv::Unknown(2, vec![1, 2])
+    ]));
+    let buffer = &mut Buffer::empty(ByteOrder::BigEndian);
+    attribute.write(buffer).unwrap();
+    assert_eq!(buffer.bytes, vec![0x80, 26, 16, 1, 0, 11, 0, 0, 0, 0, 0, 0, 0, 100, 2, 0, 5, 1, 2]);
+    assert_eq!(write_read(&attribute), attribute);
+
+    let mut attributes = vec![attribute];
+    assert_eq!(accumulated_igp_metric(&attributes), Some(100));
+    increment_accumulated_igp_metric(&mut attributes, 20);
+    assert_eq!(accumulated_igp_metric(&attributes), Some(120));
+
+    let other = vec![Attribute::new(AttributeType::AIGP, AttributeFlags::OPTIONAL, AttributeValue::AIGP(vec![
+        AIGPTlv::AccumulatedMetric(u64::MAX - 1)
+    ]))];
+    assert_eq!(compare_accumulated_igp_metric(&attributes, 10, &other, 10), Ordering::Less);
+    assert_eq!(compare_accumulated_igp_metric(&other, 10, &attributes, 10), Ordering::Greater);
+    assert_eq!(compare_accumulated_igp_metric(&attributes, 0, &[], 0), Ordering::Less);
+    assert_eq!(compare_accumulated_igp_metric(&[], 0, &[], 0), Ordering::Equal);
+
+    // The accumulated metric TLV has a fixed length
+    let buffer = &mut Buffer::from_vec(vec![0x80, 26, 7, 1, 0, 7, 0, 0, 0, 100], ByteOrder::BigEndian);
+    assert_eq!(Attribute::read(buffer).unwrap_err().ty(), ErrorType::BGPError(BGPError::update(UpdateMessageError::OptionalAttributeError)));
+}
+
+#[test]
+fn test_basic_attributes() {
+    for attribute in [
+        Attribute::new(AttributeType::MultiExitDisc, AttributeFlags::OPTIONAL, AttributeValue::MultiExitDisc(100)),
+        Attribute::new(AttributeType::LocalPref, AttributeFlags::TRANSITIVE, AttributeValue::LocalPref(200)),
+        Attribute::new(AttributeType::AtomicAggregate, AttributeFlags::TRANSITIVE, AttributeValue::AtomicAggregate)
+    ] {
+        assert_eq!(attribute, write_read(&attribute));
+    }
+}
+
+#[test]
+fn test_unknown_attributes() {
+    let transitive = AttributeFlags::OPTIONAL | AttributeFlags::TRANSITIVE;
+    let buffer = &mut Buffer::from_vec(vec![
+        transitive.bits(), 200, 2, 0xAB, 0xCD,
+        AttributeFlags::OPTIONAL.bits(), 201, 1, 0xEF
+    ], ByteOrder::BigEndian);
+    let mut attributes = vec![Attribute::read(buffer).unwrap(), Attribute::read(buffer).unwrap()];
+    assert_eq!(attributes[0].value(), &AttributeValue::Unknown(vec![0xAB, 0xCD]));
+    assert_eq!(attributes[1].value(), &AttributeValue::Unknown(vec![0xEF]));
+    assert_eq!(attributes[0].ty(), AttributeType::from(200));
+    assert_eq!(attributes[1].flags(), AttributeFlags::OPTIONAL);
+
+    propagate_unknown_attributes(&mut attributes);
+    assert_eq!(attributes.len(), 1);
+    assert_eq!(attributes[0].flags(), transitive | AttributeFlags::PARTIAL);
+
+    let buffer = &mut Buffer::empty(ByteOrder::BigEndian);
+    attributes[0].write(buffer).unwrap();
+    assert_eq!(buffer.bytes, vec![(transitive | AttributeFlags::PARTIAL).bits(), 200, 2, 0xAB, 0xCD]);
+
+    // Unrecognized well-known attributes are rejected
+    let buffer = &mut Buffer::from_vec(vec![AttributeFlags::TRANSITIVE.bits(), 200, 0], ByteOrder::BigEndian);
+    let error = Attribute::read(buffer).unwrap_err();
+    assert_eq!(error.ty(), ErrorType::BGPError(BGPError::update(UpdateMessageError::UnrecognizedWellKnownAttribute)));
+}
+
+#[test]
+fn test_only_to_customer() {
+    let otc = |autonomous_system| Attribute::new(AttributeType::OnlyToCustomer, AttributeFlags::OPTIONAL | AttributeFlags::TRANSITIVE, AttributeValue::OnlyToCustomer(autonomous_system));
+    let buffer = &mut Buffer::empty(ByteOrder::BigEndian);
+    otc(65001).write(buffer).unwrap();
+    assert_eq!(buffer.bytes, vec![0xC0, 35, 4, 0, 0, 0xFD, 0xE9]);
+    assert_eq!(write_read(&otc(65001)), otc(65001));
+
+    // Routes from a provider are marked, routes with OTC from a customer are leaks
+    let mut attributes = Vec::new();
+    assert!(only_to_customer_ingress(&mut attributes, Role::Customer, 65001));
+    assert_eq!(attributes, vec![otc(65001)]);
+    assert!(!only_to_customer_ingress(&mut attributes.clone(), Role::Provider, 65002));
+    assert!(only_to_customer_ingress(&mut attributes.clone(), Role::Peer, 65001));
+    assert!(!only_to_customer_ingress(&mut attributes.clone(), Role::Peer, 65002));
+
+    // Marked routes are only advertised to customers
+    assert!(!only_to_customer_egress(&mut attributes.clone(), Role::Customer, 65000));
+    assert!(!only_to_customer_egress(&mut attributes.clone(), Role::Peer, 65000));
+    assert!(only_to_customer_egress(&mut attributes, Role::Provider, 65000));
+    assert_eq!(attributes, vec![otc(65001)]);
+
+    let mut attributes = Vec::new();
+    assert!(only_to_customer_egress(&mut attributes, Role::Peer, 65000));
+    assert_eq!(attributes, vec![otc(65000)]);
+}
+
+#[test]
+fn test_attribute_set() {
+    let origin = Attribute::new(AttributeType::Origin, AttributeFlags::TRANSITIVE, AttributeValue::Origin(Origin::IGP));
+    let as_path = |segments| Attribute::new(AttributeType::ASPath, AttributeFlags::TRANSITIVE, AttributeValue::ASPath(ASPath::new(segments)));
+    let next_hop = Attribute::new(AttributeType::NextHop, AttributeFlags::TRANSITIVE, AttributeValue::NextHop(vec![10, 0, 0, 1]));
+    let local_pref = Attribute::new(AttributeType::LocalPref, AttributeFlags::TRANSITIVE, AttributeValue::LocalPref(200));
+
+    let attribute_set = Attribute::new(AttributeType::AttributeSet, AttributeFlags::OPTIONAL | AttributeFlags::TRANSITIVE, AttributeValue::AttributeSet(
+        65001, vec![origin.clone(), local_pref.clone()]
+    ));
+    let buffer = &mut Buffer::empty(ByteOrder::BigEndian);
+    attribute_set.write(buffer).unwrap();
+    assert_eq!(buffer.bytes, vec![0xC0, 128, 15, 0, 0, 0xFD, 0xE9, 0x40, 1, 1, 0, 0x40, 5, 4, 0, 0, 0, 200]);
+    assert_eq!(write_read(&attribute_set), attribute_set);
+
+    // The NEXT_HOP attribute isn't allowed in the ATTR_SET attribute
+    let buffer = &mut Buffer::from_vec(vec![0xC0, 128, 11, 0, 0, 0xFD, 0xE9, 0x40, 3, 4, 10, 0, 0, 1], ByteOrder::BigEndian);
+    assert_eq!(Attribute::read(buffer).unwrap_err().ty(), ErrorType::BGPError(BGPError::update(UpdateMessageError::OptionalAttributeError)));
+
+    let customer = vec![origin.clone(), as_path(vec![ASPathSegment::ASSequence(vec![65010])]), next_hop.clone(), local_pref.clone()];
+    let mut attributes = customer.clone();
+    push_attribute_set(&mut attributes, 65001);
+    assert_eq!(attributes, vec![next_hop.clone(), origin.clone(), as_path(vec![]), Attribute::new(
+        AttributeType::AttributeSet, AttributeFlags::OPTIONAL | AttributeFlags::TRANSITIVE, AttributeValue::AttributeSet(
+            65001, vec![origin.clone(), as_path(vec![ASPathSegment::ASSequence(vec![65010])]), local_pref.clone()]
+        )
+    )]);
+
+    let mut popped = attributes.clone();
+    assert!(pop_attribute_set(&mut popped, 65001));
+    assert_eq!(popped, vec![next_hop.clone(), origin.clone(), as_path(vec![ASPathSegment::ASSequence(vec![65010])]), local_pref.clone()]);
+
+    // The origin AS is prepended for a customer network with a different AS
+    assert!(pop_attribute_set(&mut attributes, 65002));
+    assert_eq!(attributes[2], as_path(vec![ASPathSegment::ASSequence(vec![65001, 65010])]));
+    assert!(!pop_attribute_set(&mut attributes, 65001));
+}
+
+#[cfg(feature = "bgp_multiprotocol")]
+#[test]
+fn test_multiprotocol_attributes() {
+    use crate::bgp::opt_params::{AFI, SAFI};
+    use crate::bgp::path_attr::MPNLRI;
+    use crate::bgp::RoutePrefix;
+
+    let reachable = Attribute::new(AttributeType::MPReachableNLRI, AttributeFlags::OPTIONAL, AttributeValue::MPReachableNLRI(
+        AFI::IPv6, SAFI::Unicast, Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 1).octets().to_vec(), MPNLRI::Prefixes(vec![RoutePrefix::IPv6(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 0), 32).into()])
+    ));
+    let unreachable = Attribute::new(AttributeType::MPUnreachableNLRI, AttributeFlags::OPTIONAL, AttributeValue::MPUnreachableNLRI(
+        AFI::IPv6, SAFI::Unicast, MPNLRI::Prefixes(vec![RoutePrefix::IPv6(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 0), 32).into()])
+    ));
+    assert_eq!(reachable, write_read(&reachable));
+    assert_eq!(unreachable, write_read(&unreachable));
+}
+
+#[cfg(feature = "bgp_multiprotocol")]
+#[test]
+fn test_multiprotocol_add_path() {
+    use crate::bgp::opt_params::{AFI, SAFI};
+    use crate::bgp::path_attr::MPNLRI;
+    use crate::bgp::{PathPrefix, RoutePrefix};
+
+    let parameters = SessionParameters {
+        add_path_receive: vec![(AFI::IPv6, SAFI::Unicast)],
+        add_path_send: vec![(AFI::IPv6, SAFI::Unicast)],
+        ..Default::default()
+    };
+    let prefix = RoutePrefix::IPv6(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 0), 32);
+    let unreachable = Attribute::new(AttributeType::MPUnreachableNLRI, AttributeFlags::OPTIONAL, AttributeValue::MPUnreachableNLRI(
+        AFI::IPv6, SAFI::Unicast, MPNLRI::Prefixes(vec![PathPrefix::new(prefix, 7)])
+    ));
+    let buffer = &mut Buffer::empty(ByteOrder::BigEndian);
+    unreachable.write_with(buffer, &parameters).unwrap();
+    assert_eq!(buffer.bytes, vec![0x80, 15, 12, 0, 2, 1, 0, 0, 0, 7, 32, 0x20, 0x01, 0x0d, 0xb8]);
+    assert_eq!(write_read_with(&unreachable, &parameters), unreachable);
+
+    // IPv4 isn't negotiated, so the prefix is read without path identifier
+    let unreachable = Attribute::new(AttributeType::MPUnreachableNLRI, AttributeFlags::OPTIONAL, AttributeValue::MPUnreachableNLRI(
+        AFI::IPv4, SAFI::Unicast, MPNLRI::Prefixes(vec![RoutePrefix::IPv4(Ipv4Addr::new(10, 0, 0, 0), 8).into()])
+    ));
+    assert_eq!(write_read_with(&unreachable, &parameters), unreachable);
+}
+
+#[cfg(feature = "bgp_multiprotocol")]
+#[test]
+fn test_multiprotocol_labeled_unicast() {
+    use crate::bgp::opt_params::{AFI, SAFI};
+    use crate::bgp::path_attr::MPNLRI;
+    use crate::bgp::{LabeledPrefix, MPLSLabel, RoutePrefix};
+
+    let prefix = RoutePrefix::IPv4(Ipv4Addr::new(192, 0, 2, 0), 24);
+    let reachable = |labels: &[u32]| Attribute::new(AttributeType::MPReachableNLRI, AttributeFlags::OPTIONAL, AttributeValue::MPReachableNLRI(
+        AFI::IPv4, SAFI::LabeledUnicast, vec![10, 0, 0, 1], MPNLRI::LabeledPrefixes(vec![LabeledPrefix::new(prefix, labels)])
+    ));
+
+    let attribute = reachable(&[16]);
+    let buffer = &mut Buffer::empty(ByteOrder::BigEndian);
+    attribute.write(buffer).unwrap();
+    assert_eq!(buffer.bytes, vec![0x80, 14, 16, 0, 1, 4, 4, 10, 0, 0, 1, 0, 48, 0x00, 0x01, 0x01, 192, 0, 2]);
+    assert_eq!(write_read(&attribute), attribute);
+
+    // Multiple labels are only encoded, if the peer announced the Multiple Labels capability
+    let parameters = SessionParameters {
+        multiple_labels: vec![(AFI::IPv4, SAFI::LabeledUnicast, 2)],
+        ..Default::default()
+    };
+    let attribute = reachable(&[16, 17]);
+    assert!(attribute.write(&mut Buffer::empty(ByteOrder::BigEndian)).is_err());
+    assert!(reachable(&[16, 17, 18]).write_with(&mut Buffer::empty(ByteOrder::BigEndian), &parameters).is_err());
+    let buffer = &mut Buffer::empty(ByteOrder::BigEndian);
+    attribute.write_with(buffer, &parameters).unwrap();
+    assert_eq!(&buffer.bytes[12..], &[72, 0x00, 0x01, 0x00, 0x00, 0x01, 0x11, 192, 0, 2]);
+    assert_eq!(write_read_with(&attribute, &parameters), attribute);
+
+    // Without the capability only the first label is read, so the prefix length is invalid
+    buffer.reset_position();
+    assert!(Attribute::read(buffer).is_err());
+
+    // The withdrawn routes contain the compatibility label
+    let unreachable = Attribute::new(AttributeType::MPUnreachableNLRI, AttributeFlags::OPTIONAL, AttributeValue::MPUnreachableNLRI(
+        AFI::IPv4, SAFI::LabeledUnicast, MPNLRI::LabeledPrefixes(vec![LabeledPrefix::withdrawal(prefix)])
+    ));
+    let buffer = &mut Buffer::empty(ByteOrder::BigEndian);
+    unreachable.write(buffer).unwrap();
+    assert_eq!(buffer.bytes, vec![0x80, 15, 10, 0, 1, 4, 48, 0x80, 0x00, 0x00, 192, 0, 2]);
+    assert_eq!(write_read_with(&unreachable, &parameters), unreachable);
+
+    // The compatibility field is read regardless of the value, even if multiple labels are negotiated
+    let buffer = &mut Buffer::from_vec(vec![0x80, 15, 10, 0, 1, 4, 48, 0x00, 0x00, 0x00, 192, 0, 2], ByteOrder::BigEndian);
+    assert_eq!(Attribute::read_with(buffer, &parameters).unwrap(), Attribute::new(AttributeType::MPUnreachableNLRI, AttributeFlags::OPTIONAL, AttributeValue::MPUnreachableNLRI(
+        AFI::IPv4, SAFI::LabeledUnicast, MPNLRI::LabeledPrefixes(vec![LabeledPrefix { prefix, labels: vec![MPLSLabel::new(0, false)], path_id: None }])
+    )));
+
+    // The labels of withdrawn routes are replaced by the compatibility field
+    let unreachable = Attribute::new(AttributeType::MPUnreachableNLRI, AttributeFlags::OPTIONAL, AttributeValue::MPUnreachableNLRI(
+        AFI::IPv4, SAFI::LabeledUnicast, MPNLRI::LabeledPrefixes(vec![LabeledPrefix::new(prefix, &[16, 17])])
+    ));
+    let buffer = &mut Buffer::empty(ByteOrder::BigEndian);
+    unreachable.write_with(buffer, &parameters).unwrap();
+    assert_eq!(buffer.bytes, vec![0x80, 15, 10, 0, 1, 4, 48, 0x80, 0x00, 0x00, 192, 0, 2]);
+    buffer.reset_position();
+    assert_eq!(Attribute::read_with(buffer, &parameters).unwrap(), Attribute::new(AttributeType::MPUnreachableNLRI, AttributeFlags::OPTIONAL, AttributeValue::MPUnreachableNLRI(
+        AFI::IPv4, SAFI::LabeledUnicast, MPNLRI::LabeledPrefixes(vec![LabeledPrefix::withdrawal(prefix)])
+    )));
+
+    let AttributeValue::MPReachableNLRI(_, _, _, MPNLRI::LabeledPrefixes(prefixes)) = attribute.value() else {
+        panic!("Expected labeled prefixes");
+    };
+    assert_eq!(prefixes[0].labels[1], MPLSLabel::new(17, true));
+    assert_eq!(prefixes[0].to_string(), "192.0.2.0/24 (labels 16/17)");
+}
+
+#[cfg(feature = "bgp_multiprotocol")]
+#[test]
+fn test_multiprotocol_prefix_family() {
+    use crate::bgp::opt_params::{AFI, SAFI};
+    use crate::bgp::path_attr::MPNLRI;
+    use crate::bgp::{LabeledPrefix, RoutePrefix};
+
+    let prefix = RoutePrefix::IPv6(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 0), 32);
+    let reachable = Attribute::new(AttributeType::MPReachableNLRI, AttributeFlags::OPTIONAL, AttributeValue::MPReachableNLRI(
+        AFI::IPv4, SAFI::Unicast, vec![10, 0, 0, 1], MPNLRI::Prefixes(vec![prefix.into()])
+    ));
+    assert!(reachable.write(&mut Buffer::empty(ByteOrder::BigEndian)).is_err());
+    let unreachable = Attribute::new(AttributeType::MPUnreachableNLRI, AttributeFlags::OPTIONAL, AttributeValue::MPUnreachableNLRI(
+        AFI::IPv4, SAFI::LabeledUnicast, MPNLRI::LabeledPrefixes(vec![LabeledPrefix::withdrawal(prefix)])
+    ));
+    assert!(unreachable.write(&mut Buffer::empty(ByteOrder::BigEndian)).is_err());
+}
@@ -0,0 +1,59 @@
+use core::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use crate::bgp::error::{BGPError, UpdateMessageError};
+use crate::bgp::path_attr::pmsi_tunnel::{PMSITunnel, PMSITunnelFlags, PMSITunnelIdentifier};
+use crate::bgp::path_attr::{Attribute, AttributeFlags, AttributeType, AttributeValue};
+use crate::error::ErrorType;
+use crate::if_no_std;
+use crate::io::{Buffer, ByteOrder, WriteRead};
+use super::write_read;
+
+if_no_std! {
+    use alloc::vec;
+}
+
+fn pmsi_tunnel(flags: PMSITunnelFlags, label: u32, tunnel_identifier: PMSITunnelIdentifier) -> Attribute {
+    Attribute::new(AttributeType::PMSITunnel, AttributeFlags::OPTIONAL | AttributeFlags::TRANSITIVE, AttributeValue::PMSITunnel(PMSITunnel {
+        flags,
+        label,
+        tunnel_identifier
+    }))
+}
+
+#[test]
+fn test_ingress_replication() {
+    let attribute = pmsi_tunnel(PMSITunnelFlags::NONE, 10010, PMSITunnelIdentifier::IngressReplication(IpAddr::V4(Ipv4Addr::new(192, 0, 2, 1))));
+    let buffer = &mut Buffer::empty(ByteOrder::BigEndian);
+    attribute.write(buffer).unwrap();
+    assert_eq!(buffer.bytes, vec![0xC0, 22, 9, 0, 6, 0, 0x27, 0x1A, 192, 0, 2, 1]);
+    assert_eq!(write_read(&attribute), attribute);
+}
+
+#[test]
+fn test_tunnel_identifiers() {
+    let address = IpAddr::V4(Ipv4Addr::new(192, 0, 2, 1));
+    let group = IpAddr::V4(Ipv4Addr::new(232, 1, 1, 1));
+    for tunnel_identifier in [
+        PMSITunnelIdentifier::NoTunnelInformation,
+        PMSITunnelIdentifier::RSVPTEP2MPLSP(address, 1, 2),
+        PMSITunnelIdentifier::RSVPTEP2MPLSP(IpAddr::V6(Ipv6Addr::LOCALHOST), 1, 2),
+        PMSITunnelIdentifier::MLDPP2MPLSP(address, vec![1, 0, 4, 0, 0, 0, 1]),
+        PMSITunnelIdentifier::PIMSSMTree(address, group),
+        PMSITunnelIdentifier::PIMSMTree(address, group),
+        PMSITunnelIdentifier::BIDIRPIMTree(IpAddr::V6(Ipv6Addr::LOCALHOST), IpAddr::V6(Ipv6Addr::UNSPECIFIED)),
+        PMSITunnelIdentifier::IngressReplication(IpAddr::V6(Ipv6Addr::LOCALHOST)),
+        PMSITunnelIdentifier::MLDPMP2MPLSP(IpAddr::V6(Ipv6Addr::LOCALHOST), vec![]),
+        PMSITunnelIdentifier::Unknown(8, vec![1, 2, 3])
+    ] {
+        let attribute = pmsi_tunnel(PMSITunnelFlags::LEAF_INFORMATION_REQUIRED, 16 << 4, tunnel_identifier);
+        assert_eq!(write_read(&attribute), attribute);
+    }
+}
+
+#[test]
+fn test_malformed_pmsi_tunnel() {
+    let buffer = &mut Buffer::from_vec(vec![0xC0, 22, 8, 0, 6, 0, 0, 0, 192, 0, 2], ByteOrder::BigEndian);
+    assert_eq!(Attribute::read(buffer).unwrap_err().ty(), ErrorType::BGPError(BGPError::update(UpdateMessageError::OptionalAttributeError)));
+
+    let attribute = pmsi_tunnel(PMSITunnelFlags::NONE, 1 << 24, PMSITunnelIdentifier::NoTunnelInformation);
+    assert!(attribute.write(&mut Buffer::empty(ByteOrder::BigEndian)).is_err());
+}
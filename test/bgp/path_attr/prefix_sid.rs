@@ -0,0 +1,65 @@
+use core::net::Ipv6Addr;
+use crate::bgp::error::{BGPError, UpdateMessageError};
+use crate::bgp::path_attr::prefix_sid::{PrefixSIDTlv, SRGBRange, SRv6SIDInformation, SRv6SIDStructure, SRv6ServiceDataSubSubTlv, SRv6ServiceSubTlv};
+use crate::bgp::path_attr::{Attribute, AttributeFlags, AttributeType, AttributeValue};
+use crate::error::ErrorType;
+use crate::if_no_std;
+use crate::io::{Buffer, ByteOrder, WriteRead};
+use super::write_read;
+
+if_no_std! {
+    use alloc::{vec, vec::Vec};
+}
+
+#[test]
+fn test_sr_mpls_prefix_sid() {
+    let attribute = Attribute::new(AttributeType::BGPPrefixSID, AttributeFlags::OPTIONAL | AttributeFlags::TRANSITIVE, AttributeValue::PrefixSID(vec![
+        PrefixSIDTlv::LabelIndex(0, 100),
+        PrefixSIDTlv::OriginatorSRGB(0, vec![SRGBRange { base: 16000, range: 8000 }])
+    ]));
+    let buffer = &mut Buffer::empty(ByteOrder::BigEndian);
+    attribute.write(buffer).unwrap();
+    assert_eq!(buffer.bytes, vec![
+        0xC0, 40, 21,
+        1, 0, 7, 0, 0, 0, 0, 0, 0, 100,
+        3, 0, 8, 0, 0, 0, 0x3E, 0x80, 0, 0x1F, 0x40
+    ]);
+    assert_eq!(write_read(&attribute), attribute);
+
+    // The Label-Index TLV has a fixed length
+    let buffer = &mut Buffer::from_vec(vec![0xC0, 40, 9, 1, 0, 6, 0, 0, 0, 0, 0, 100], ByteOrder::BigEndian);
+    assert_eq!(Attribute::read(buffer).unwrap_err().ty(), ErrorType::BGPError(BGPError::update(UpdateMessageError::OptionalAttributeError)));
+}
+
+#[test]
+fn test_srv6_prefix_sid() {
+    let information = SRv6SIDInformation {
+        sid: Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 0),
+        flags: 0,
+        endpoint_behavior: 0x13,
+        sub_sub_tlvs: vec![
+            SRv6ServiceDataSubSubTlv::SIDStructure(SRv6SIDStructure {
+                locator_block_length: 32,
+                locator_node_length: 16,
+                function_length: 16,
+                argument_length: 0,
+                transposition_length: 16,
+                transposition_offset: 48
+            }),
+            SRv6ServiceDataSubSubTlv::Unknown(2, vec![1, 2, 3])
+        ]
+    };
+    let attribute = Attribute::new(AttributeType::BGPPrefixSID, AttributeFlags::OPTIONAL | AttributeFlags::TRANSITIVE, AttributeValue::PrefixSID(vec![
+        PrefixSIDTlv::SRv6L3Service(vec![SRv6ServiceSubTlv::SIDInformation(information.clone())]),
+        PrefixSIDTlv::SRv6L2Service(vec![
+            SRv6ServiceSubTlv::SIDInformation(information),
+            SRv6ServiceSubTlv::Unknown(7, vec![0xFF])
+        ]),
+        PrefixSIDTlv::Unknown(2, vec![0, 1])
+    ]));
+    assert_eq!(write_read(&attribute), attribute);
+
+    let buffer = &mut Buffer::empty(ByteOrder::BigEndian);
+    PrefixSIDTlv::SRv6L3Service(Vec::new()).write(buffer).unwrap();
+    assert_eq!(buffer.bytes, vec![5, 0, 1, 0]);
+}
@@ -0,0 +1,85 @@
+use core::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use crate::bgp::error::{BGPError, UpdateMessageError};
+use crate::bgp::path_attr::tunnel_encap::{SegmentListSubTlv, TunnelEncapsulation, TunnelSubTlv, TunnelTlv, TunnelType};
+use crate::bgp::path_attr::{Attribute, AttributeFlags, AttributeType, AttributeValue};
+use crate::error::ErrorType;
+use crate::if_no_std;
+use crate::io::{Buffer, ByteOrder, WriteRead};
+use super::write_read;
+
+if_no_std! {
+    use alloc::vec;
+}
+
+#[test]
+fn test_vxlan_tunnel() {
+    let attribute = Attribute::new(AttributeType::TunnelEncapsulation, AttributeFlags::OPTIONAL | AttributeFlags::TRANSITIVE, AttributeValue::TunnelEncapsulation(vec![
+        TunnelTlv {
+            tunnel_type: TunnelType::VXLAN,
+            sub_tlvs: vec![
+                TunnelSubTlv::Encapsulation(TunnelEncapsulation::VXLAN(Some(10010), None)),
+                TunnelSubTlv::RemoteEndpoint(65001, Some(IpAddr::V4(Ipv4Addr::new(192, 0, 2, 1)))),
+                TunnelSubTlv::Color(100)
+            ]
+        }
+    ]));
+    let buffer = &mut Buffer::empty(ByteOrder::BigEndian);
+    attribute.write(buffer).unwrap();
+    assert_eq!(buffer.bytes, vec![
+        0xC0, 23, 40,
+        0, 8, 0, 36,
+        1, 12, 0x80, 0, 0x27, 0x1A, 0, 0, 0, 0, 0, 0, 0, 0,
+        6, 10, 0, 0, 0xFD, 0xE9, 0, 1, 192, 0, 2, 1,
+        4, 8, 0x03, 0x0B, 0, 0, 0, 0, 0, 100
+    ]);
+    assert_eq!(write_read(&attribute), attribute);
+}
+
+#[test]
+fn test_tunnel_types() {
+    let attribute = Attribute::new(AttributeType::TunnelEncapsulation, AttributeFlags::OPTIONAL | AttributeFlags::TRANSITIVE | AttributeFlags::EXTENDED_LENGTH, AttributeValue::TunnelEncapsulation(vec![
+        TunnelTlv {
+            tunnel_type: TunnelType::GRE,
+            sub_tlvs: vec![
+                TunnelSubTlv::Encapsulation(TunnelEncapsulation::GRE(42)),
+                TunnelSubTlv::ProtocolType(0x0800)
+            ]
+        },
+        TunnelTlv {
+            tunnel_type: TunnelType::MPLSInUDP,
+            sub_tlvs: vec![
+                TunnelSubTlv::RemoteEndpoint(4200000000, Some(IpAddr::V6(Ipv6Addr::LOCALHOST))),
+                TunnelSubTlv::UDPDestinationPort(6635),
+                TunnelSubTlv::Unknown(7, vec![0x2E])
+            ]
+        },
+        TunnelTlv {
+            tunnel_type: TunnelType::SRPolicy,
+            sub_tlvs: vec![
+                TunnelSubTlv::Unknown(12, vec![0, 0, 0, 0, 0, 100]),
+                TunnelSubTlv::SegmentList(vec![
+                    SegmentListSubTlv::Weight(0, 1),
+                    SegmentListSubTlv::MPLSSegment(0, 16001 << 12),
+                    SegmentListSubTlv::SRv6Segment(0, Ipv6Addr::LOCALHOST, None),
+                    SegmentListSubTlv::SRv6Segment(0x08, Ipv6Addr::LOCALHOST, Some([0, 0, 0, 0x13, 32, 16, 16, 0]))
+                ]),
+                TunnelSubTlv::Unknown(129, vec![b'a'; 300])
+            ]
+        },
+        TunnelTlv {
+            tunnel_type: TunnelType::Unknown(100),
+            sub_tlvs: vec![TunnelSubTlv::Encapsulation(TunnelEncapsulation::Unknown(vec![1, 2, 3]))]
+        }
+    ]));
+    assert_eq!(write_read(&attribute), attribute);
+}
+
+#[test]
+fn test_malformed_tunnel() {
+    // The Color sub-TLV must contain a Color Extended Community
+    let buffer = &mut Buffer::from_vec(vec![0xC0, 23, 14, 0, 8, 0, 10, 4, 8, 0x00, 0x02, 0, 0, 0, 0, 0, 100], ByteOrder::BigEndian);
+    assert_eq!(Attribute::read(buffer).unwrap_err().ty(), ErrorType::BGPError(BGPError::update(UpdateMessageError::OptionalAttributeError)));
+
+    let buffer = &mut Buffer::from_vec(vec![0xC0, 23, 7, 0, 8, 0, 3, 8, 1, 0], ByteOrder::BigEndian);
+    assert_eq!(Attribute::read(buffer).unwrap_err().ty(), ErrorType::BGPError(BGPError::update(UpdateMessageError::OptionalAttributeError)));
+}
@@ -1,7 +1,12 @@
+use core::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use core::str::FromStr;
+use crate::bgp::error::{BGPError, UpdateMessageError};
+use crate::bgp::opt_params::AFI;
+use crate::error::ErrorType;
 use crate::{bgp::RoutePrefix, if_no_std, io::{Buffer, ByteOrder, WriteRead}};
 
 if_no_std! {
-    use alloc::vec;
+    use alloc::{string::ToString, vec, vec::Vec};
 }
 
 macro_rules! ipv4_prefix_test {
@@ -9,9 +14,13 @@ macro_rules! ipv4_prefix_test {
         paste::paste! {
             #[test]
             fn [<test_ipv4_ $length>]() {
-                let prefix1 = RoutePrefix::IPv4($length, $prefix);
+                let bytes: Vec<u8> = $prefix;
+                let mut octets = [0; 4];
+                octets[..bytes.len()].copy_from_slice(&bytes);
+                let prefix1 = RoutePrefix::IPv4(Ipv4Addr::from(octets), $length);
                 let buffer = &mut Buffer::empty(ByteOrder::BigEndian);
                 prefix1.write(buffer).unwrap();
+                assert_eq!(buffer.bytes, [&[$length][..], &bytes[..]].concat());
                 buffer.reset_position();
                 let prefix2 = RoutePrefix::read(buffer).unwrap();
                 assert_eq!(prefix1, prefix2);
@@ -61,3 +70,89 @@ ipv4_prefix_test!(29, vec![255, 255, 255, 248]);
 ipv4_prefix_test!(30, vec![255, 255, 255, 252]);
 ipv4_prefix_test!(31, vec![255, 255, 255, 254]);
 ipv4_prefix_test!(32, vec![255, 255, 255, 255]);
+
+// IPv6
+
+#[test]
+fn test_ipv6_prefixes() {
+    for (prefix, bytes) in [
+        (RoutePrefix::IPv6(Ipv6Addr::UNSPECIFIED, 0), vec![0]),
+        (RoutePrefix::IPv6(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 0), 32), vec![32, 0x20, 0x01, 0x0d, 0xb8]),
+        (RoutePrefix::IPv6(Ipv6Addr::new(0x2001, 0xdb8, 0x1200, 0, 0, 0, 0, 0), 40), vec![40, 0x20, 0x01, 0x0d, 0xb8, 0x12]),
+        (RoutePrefix::IPv6(Ipv6Addr::LOCALHOST, 128), [&[128][..], &Ipv6Addr::LOCALHOST.octets()[..]].concat())
+    ] {
+        let buffer = &mut Buffer::empty(ByteOrder::BigEndian);
+        prefix.write(buffer).unwrap();
+        assert_eq!(buffer.bytes, bytes);
+        buffer.reset_position();
+        assert_eq!(RoutePrefix::read_with(buffer, AFI::IPv6).unwrap(), prefix);
+    }
+
+    let buffer = &mut Buffer::from_vec([&[129][..], &[0; 17][..]].concat(), ByteOrder::BigEndian);
+    assert!(RoutePrefix::read_with(buffer, AFI::IPv6).is_err());
+}
+
+#[test]
+fn test_prefix_containment() {
+    let prefix = RoutePrefix::IPv4(Ipv4Addr::new(192, 0, 2, 0), 24);
+    assert!(prefix.contains(&RoutePrefix::IPv4(Ipv4Addr::new(192, 0, 2, 128), 25)));
+    assert!(prefix.contains(&prefix));
+    assert!(!prefix.contains(&RoutePrefix::IPv4(Ipv4Addr::new(192, 0, 0, 0), 16)));
+    assert!(!prefix.contains(&RoutePrefix::IPv4(Ipv4Addr::new(192, 0, 3, 0), 24)));
+    assert!(prefix.contains_address(IpAddr::V4(Ipv4Addr::new(192, 0, 2, 1))));
+    assert!(!prefix.contains_address(IpAddr::V6(Ipv6Addr::LOCALHOST)));
+    assert!(RoutePrefix::IPv4(Ipv4Addr::UNSPECIFIED, 0).contains(&prefix));
+
+    let prefix = RoutePrefix::from((IpAddr::V6(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 0)), 32));
+    assert!(prefix.contains_address(IpAddr::V6(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 1))));
+    assert!(!prefix.contains_address(IpAddr::V6(Ipv6Addr::new(0x2001, 0xdb9, 0, 0, 0, 0, 0, 1))));
+    assert_eq!(<(IpAddr, u8)>::from(prefix), (prefix.address(), 32));
+
+    let mut prefixes = vec![prefix, RoutePrefix::IPv4(Ipv4Addr::new(10, 0, 0, 0), 16), RoutePrefix::IPv4(Ipv4Addr::new(10, 0, 0, 0), 8)];
+    prefixes.sort();
+    assert_eq!(prefixes, vec![RoutePrefix::IPv4(Ipv4Addr::new(10, 0, 0, 0), 8), RoutePrefix::IPv4(Ipv4Addr::new(10, 0, 0, 0), 16), prefix]);
+}
+
+#[test]
+fn test_prefix_normalisation() {
+    let prefix = RoutePrefix::IPv4(Ipv4Addr::new(192, 0, 2, 1), 24);
+    assert!(!prefix.is_normalized());
+    assert_eq!(prefix.normalized(), RoutePrefix::IPv4(Ipv4Addr::new(192, 0, 2, 0), 24));
+    assert!(RoutePrefix::IPv4(Ipv4Addr::new(192, 0, 2, 1), 32).is_normalized());
+
+    let prefix = RoutePrefix::IPv6(Ipv6Addr::new(0x2001, 0xdb8, 0xffff, 0, 0, 0, 0, 1), 33);
+    assert_eq!(prefix.normalized(), RoutePrefix::IPv6(Ipv6Addr::new(0x2001, 0xdb8, 0x8000, 0, 0, 0, 0, 0), 33));
+
+    let buffer = &mut Buffer::from_vec(vec![200, 255, 255, 255, 255], ByteOrder::BigEndian);
+    assert_eq!(RoutePrefix::read(buffer).unwrap_err().ty(), ErrorType::BGPError(BGPError::update(UpdateMessageError::InvalidNetworkField)));
+}
+
+#[test]
+fn test_prefix_notation() {
+    for (notation, prefix) in [
+        ("192.0.2.0/24", RoutePrefix::IPv4(Ipv4Addr::new(192, 0, 2, 0), 24)),
+        ("0.0.0.0/0", RoutePrefix::IPv4(Ipv4Addr::UNSPECIFIED, 0)),
+        ("2001:db8::/32", RoutePrefix::IPv6(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 0), 32)),
+        ("::1/128", RoutePrefix::IPv6(Ipv6Addr::LOCALHOST, 128))
+    ] {
+        assert_eq!(RoutePrefix::from_str(notation).unwrap(), prefix);
+        assert_eq!(prefix.to_string(), notation);
+    }
+
+    for notation in ["192.0.2.0", "192.0.2.1/24", "192.0.2.0/33", "2001:db8::1/32", "2001:db8::/129", "example/8", "10.0.0.0/-1"] {
+        assert_eq!(RoutePrefix::from_str(notation).unwrap_err().ty(), ErrorType::ParseError);
+    }
+}
+
+#[test]
+fn test_prefix_family() {
+    use crate::bgp::Packet;
+    let prefix = RoutePrefix::IPv6(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 0), 32);
+    assert!(prefix.write_with(&mut Buffer::empty(ByteOrder::BigEndian), AFI::IPv6).is_ok());
+    let error = prefix.write_with(&mut Buffer::empty(ByteOrder::BigEndian), AFI::IPv4).unwrap_err();
+    assert_eq!(error.ty(), ErrorType::WriteError);
+
+    // The NLRI and the withdrawn routes of the Update packet only contain IPv4 prefixes
+    let packet = Packet::Update(vec![prefix.into()], Vec::new(), Vec::new());
+    assert_eq!(packet.write(&mut Buffer::empty(ByteOrder::BigEndian)).unwrap_err().ty(), ErrorType::WriteError);
+}